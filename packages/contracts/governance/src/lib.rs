@@ -1,443 +1,2533 @@
-use anchor_lang::prelude::*;
-use anchor_lang::solana_program::clock::Clock;
-use anchor_spl::token::{self, Token, TokenAccount};
-
-declare_id!("Govz1Dj1JhKqXGfdRg7gG2LgYMPJhH5kR2vWGtJfFd2A");
-
-// Constants for rate limiting
-pub const PROPOSAL_COOLDOWN: i64 = 86400; // 24 hours between proposals from same proposer
-pub const MAX_CONCURRENT_PROPOSALS: u32 = 10;
-pub const MIN_VOTING_POWER: u64 = 1000 * 10u64.pow(9); // 1000 tokens with 9 decimals
-
-#[program]
-pub mod governance {
-    use super::*;
-
-    // Events
-    #[event]
-    pub struct ProposalCreated {
-        pub id: u64,
-        pub proposer: Pubkey,
-        pub title: String,
-        pub timestamp: i64,
-    }
-
-    #[event]
-    pub struct VoteCast {
-        pub proposal_id: u64,
-        pub voter: Pubkey,
-        pub vote_type: VoteType,
-        pub voting_power: u64,
-        pub timestamp: i64,
-    }
-
-    #[event]
-    pub struct ProposalExecuted {
-        pub id: u64,
-        pub executor: Pubkey,
-        pub timestamp: i64,
-    }
-
-    #[event]
-    pub struct EmergencyAction {
-        pub action_type: EmergencyActionType,
-        pub initiator: Pubkey,
-        pub timestamp: i64,
-    }
-
-    pub fn create_proposal(ctx: Context<CreateProposal>, proposal: ProposalInput) -> Result<()> {
-        let governance = &mut ctx.accounts.governance;
-        require!(!governance.paused, GovernanceError::ContractPaused);
-
-        require!(
-            proposal.title.len() <= 128,
-            GovernanceError::TitleTooLong
-        );
-        require!(
-            proposal.description.len() <= 1024,
-            GovernanceError::DescriptionTooLong
-        );
-
-        let token_balance = ctx.accounts.proposer_tokens.amount;
-        require!(
-            token_balance >= MIN_VOTING_POWER,
-            GovernanceError::InsufficientTokens
-        );
-
-        // Rate limiting checks
-        let current_time = Clock::get()?.unix_timestamp;
-        let proposer = ctx.accounts.proposer.key();
-        
-        if let Some(last_proposal_time) = governance.last_proposal_times.get(&proposer) {
-            require!(
-                current_time >= last_proposal_time + PROPOSAL_COOLDOWN,
-                GovernanceError::ProposalCooldownActive
-            );
-        }
-
-        require!(
-            governance.active_proposal_count < MAX_CONCURRENT_PROPOSALS,
-            GovernanceError::TooManyActiveProposals
-        );
-
-        let proposal_account = &mut ctx.accounts.proposal;
-        proposal_account.id = proposal.id;
-        proposal_account.proposer = proposer;
-        proposal_account.title = proposal.title;
-        proposal_account.description = proposal.description;
-        proposal_account.execution_plan = proposal.execution_plan;
-        proposal_account.votes = VoteCount::default();
-        proposal_account.status = ProposalStatus::Active;
-        proposal_account.created_at = current_time;
-        proposal_account.voting_ends_at = current_time + 86400; // 24 hours
-        proposal_account.total_voting_power = 0;
-        proposal_account.quorum_reached = false;
-
-        // Update governance state
-        governance.last_proposal_times.insert(proposer, current_time);
-        governance.active_proposal_count += 1;
-
-        emit!(ProposalCreated {
-            id: proposal.id,
-            proposer,
-            title: proposal.title.clone(),
-            timestamp: current_time,
-        });
-
-        Ok(())
-    }
-
-    pub fn vote(ctx: Context<Vote>, vote_type: VoteType) -> Result<()> {
-        let governance = &ctx.accounts.governance;
-        require!(!governance.paused, GovernanceError::ContractPaused);
-
-        let proposal = &mut ctx.accounts.proposal;
-        let voter_tokens = ctx.accounts.voter_tokens.amount;
-        let current_time = Clock::get()?.unix_timestamp;
-
-        require!(
-            proposal.status == ProposalStatus::Active,
-            GovernanceError::ProposalNotActive
-        );
-        require!(
-            current_time <= proposal.voting_ends_at,
-            GovernanceError::VotingEnded
-        );
-        require!(
-            voter_tokens > 0,
-            GovernanceError::NoVotingPower
-        );
-
-        // Check for double voting
-        require!(
-            !proposal.has_voted(&ctx.accounts.voter.key()),
-            GovernanceError::AlreadyVoted
-        );
-
-        // Update vote counts
-        match vote_type {
-            VoteType::Yes => {
-                proposal.votes.yes += voter_tokens;
-            }
-            VoteType::No => {
-                proposal.votes.no += voter_tokens;
-            }
-            VoteType::Abstain => {
-                proposal.votes.abstain += voter_tokens;
-            }
-        }
-
-        proposal.total_voting_power += voter_tokens;
-        proposal.voters.push(ctx.accounts.voter.key());
-
-        // Check if quorum is reached (20% of total supply)
-        if proposal.total_voting_power >= 20_000_000 * 10u64.pow(9) { // 20M tokens
-            proposal.quorum_reached = true;
-        }
-
-        // Check if voting period has ended
-        if current_time >= proposal.voting_ends_at {
-            finalize_proposal(proposal)?;
-            if proposal.status == ProposalStatus::Failed {
-                let governance = &mut ctx.accounts.governance;
-                governance.active_proposal_count -= 1;
-            }
-        }
-
-        emit!(VoteCast {
-            proposal_id: proposal.id,
-            voter: ctx.accounts.voter.key(),
-            vote_type,
-            voting_power: voter_tokens,
-            timestamp: current_time,
-        });
-
-        Ok(())
-    }
-
-    pub fn execute(ctx: Context<Execute>) -> Result<()> {
-        let governance = &ctx.accounts.governance;
-        require!(!governance.paused, GovernanceError::ContractPaused);
-
-        let proposal = &mut ctx.accounts.proposal;
-        let current_time = Clock::get()?.unix_timestamp;
-
-        require!(
-            proposal.status == ProposalStatus::Passed,
-            GovernanceError::ProposalNotPassed
-        );
-        require!(
-            current_time >= proposal.voting_ends_at + 43200, // 12 hour timelock
-            GovernanceError::TimelockNotExpired
-        );
-
-        proposal.status = ProposalStatus::Executed;
-        
-        let governance = &mut ctx.accounts.governance;
-        governance.active_proposal_count -= 1;
-
-        emit!(ProposalExecuted {
-            id: proposal.id,
-            executor: ctx.accounts.executor.key(),
-            timestamp: current_time,
-        });
-
-        Ok(())
-    }
-
-    pub fn pause(ctx: Context<EmergencyAction>) -> Result<()> {
-        let governance = &mut ctx.accounts.governance;
-        require!(
-            ctx.accounts.admin.key() == governance.emergency_admin,
-            GovernanceError::Unauthorized
-        );
-        
-        governance.paused = true;
-
-        emit!(EmergencyAction {
-            action_type: EmergencyActionType::Pause,
-            initiator: ctx.accounts.admin.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-
-        Ok(())
-    }
-
-    pub fn unpause(ctx: Context<EmergencyAction>) -> Result<()> {
-        let governance = &mut ctx.accounts.governance;
-        require!(
-            ctx.accounts.admin.key() == governance.emergency_admin,
-            GovernanceError::Unauthorized
-        );
-        
-        governance.paused = false;
-
-        emit!(EmergencyAction {
-            action_type: EmergencyActionType::Unpause,
-            initiator: ctx.accounts.admin.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-
-        Ok(())
-    }
-
-    pub fn update_emergency_admin(ctx: Context<EmergencyAction>, new_admin: Pubkey) -> Result<()> {
-        let governance = &mut ctx.accounts.governance;
-        require!(
-            ctx.accounts.admin.key() == governance.emergency_admin,
-            GovernanceError::Unauthorized
-        );
-        
-        governance.emergency_admin = new_admin;
-
-        emit!(EmergencyAction {
-            action_type: EmergencyActionType::UpdateAdmin,
-            initiator: ctx.accounts.admin.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-
-        Ok(())
-    }
-}
-
-fn finalize_proposal(proposal: &mut Account<Proposal>) -> Result<()> {
-    if !proposal.quorum_reached {
-        proposal.status = ProposalStatus::Failed;
-        return Ok(());
-    }
-
-    let total_votes = proposal.votes.yes + proposal.votes.no;
-    if total_votes == 0 {
-        proposal.status = ProposalStatus::Failed;
-        return Ok(());
-    }
-
-    let yes_percentage = (proposal.votes.yes as f64 / total_votes as f64) * 100.0;
-    if yes_percentage > 66.0 { // 66% majority required
-        proposal.status = ProposalStatus::Passed;
-    } else {
-        proposal.status = ProposalStatus::Failed;
-    }
-
-    Ok(())
-}
-
-#[derive(Accounts)]
-pub struct CreateProposal<'info> {
-    #[account(mut)]
-    pub governance: Account<'info, Governance>,
-    #[account(init, payer = proposer, space = Proposal::LEN)]
-    pub proposal: Account<'info, Proposal>,
-    #[account(mut)]
-    pub proposer: Signer<'info>,
-    pub proposer_tokens: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-pub struct Vote<'info> {
-    #[account(mut)]
-    pub governance: Account<'info, Governance>,
-    #[account(mut)]
-    pub proposal: Account<'info, Proposal>,
-    pub voter: Signer<'info>,
-    pub voter_tokens: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-}
-
-#[derive(Accounts)]
-pub struct Execute<'info> {
-    #[account(mut)]
-    pub governance: Account<'info, Governance>,
-    #[account(mut)]
-    pub proposal: Account<'info, Proposal>,
-    pub executor: Signer<'info>,
-    pub token_program: Program<'info, Token>,
-}
-
-#[derive(Accounts)]
-pub struct EmergencyAction<'info> {
-    #[account(mut)]
-    pub governance: Account<'info, Governance>,
-    pub admin: Signer<'info>,
-}
-
-#[account]
-pub struct Governance {
-    pub emergency_admin: Pubkey,
-    pub paused: bool,
-    pub active_proposal_count: u32,
-    pub last_proposal_times: std::collections::BTreeMap<Pubkey, i64>,
-}
-
-impl Governance {
-    pub const LEN: usize = 8 + // discriminator
-        32 + // emergency_admin
-        1 + // paused
-        4 + // active_proposal_count
-        1024; // last_proposal_times (approximate space for BTreeMap)
-}
-
-#[account]
-pub struct Proposal {
-    pub id: u64,
-    pub proposer: Pubkey,
-    pub title: String,
-    pub description: String,
-    pub execution_plan: Vec<u8>,
-    pub votes: VoteCount,
-    pub status: ProposalStatus,
-    pub created_at: i64,
-    pub voting_ends_at: i64,
-    pub total_voting_power: u64,
-    pub quorum_reached: bool,
-    pub voters: Vec<Pubkey>,
-}
-
-impl Proposal {
-    pub const LEN: usize = 8 + // discriminator
-        8 + // id
-        32 + // proposer
-        4 + 128 + // title
-        4 + 1024 + // description
-        4 + 1024 + // execution_plan
-        24 + // votes
-        1 + // status
-        8 + // created_at
-        8 + // voting_ends_at
-        8 + // total_voting_power
-        1 + // quorum_reached
-        4 + (32 * 100); // voters (max 100 voters)
-
-    pub fn has_voted(&self, voter: &Pubkey) -> bool {
-        self.voters.contains(voter)
-    }
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
-pub struct VoteCount {
-    pub yes: u64,
-    pub no: u64,
-    pub abstain: u64,
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Clone)]
-pub enum ProposalStatus {
-    Active,
-    Passed,
-    Failed,
-    Executed,
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
-pub enum VoteType {
-    Yes,
-    No,
-    Abstain,
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize)]
-pub struct ProposalInput {
-    pub id: u64,
-    pub title: String,
-    pub description: String,
-    pub execution_plan: Vec<u8>,
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize)]
-pub enum EmergencyActionType {
-    Pause,
-    Unpause,
-    UpdateAdmin,
-}
-
-#[error_code]
-pub enum GovernanceError {
-    #[msg("Title exceeds maximum length")]
-    TitleTooLong,
-    #[msg("Description exceeds maximum length")]
-    DescriptionTooLong,
-    #[msg("Insufficient tokens to create proposal")]
-    InsufficientTokens,
-    #[msg("Proposal is not active")]
-    ProposalNotActive,
-    #[msg("Voting period has ended")]
-    VotingEnded,
-    #[msg("No voting power")]
-    NoVotingPower,
-    #[msg("Proposal has not passed")]
-    ProposalNotPassed,
-    #[msg("Timelock period has not expired")]
-    TimelockNotExpired,
-    #[msg("Contract is paused")]
-    ContractPaused,
-    #[msg("Proposal cooldown period active")]
-    ProposalCooldownActive,
-    #[msg("Too many active proposals")]
-    TooManyActiveProposals,
-    #[msg("Already voted on this proposal")]
-    AlreadyVoted,
-    #[msg("Unauthorized")]
-    Unauthorized,
-}
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::clock::Clock;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+declare_id!("Govz1Dj1JhKqXGfdRg7gG2LgYMPJhH5kR2vWGtJfFd2A");
+
+// Proposal limits, rate limits and timings live in GovernanceConfig
+pub const MAX_EXECUTION_PLAN_BYTES: usize = 1024; // shared by all of a proposal's plans
+pub const MAX_PROPOSAL_OPTIONS: usize = 8;
+pub const MAX_OPTION_LABEL_LEN: usize = 32;
+
+// Basis point denominator for config thresholds
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+// Vote-escrow lockups, voting power decays linearly to zero at unlock
+pub const MIN_LOCK_DURATION: i64 = 604_800; // 1 week
+pub const MAX_LOCK_DURATION: i64 = 126_144_000; // 4 years
+pub const MAX_LOCK_CHECKPOINTS: usize = 16;
+
+// PDA seeds
+pub const GOVERNANCE_AUTHORITY_SEED: &[u8] = b"governance_authority";
+pub const LOCK_VAULT_SEED: &[u8] = b"lock_vault";
+pub const DEPOSIT_VAULT_SEED: &[u8] = b"deposit_vault";
+pub const VOTE_LOCK_SEED: &[u8] = b"vote_lock";
+pub const VOTER_WEIGHT_SEED: &[u8] = b"voter_weight";
+pub const DELEGATION_SEED: &[u8] = b"delegation";
+pub const VOTE_RECORD_SEED: &[u8] = b"vote_record";
+pub const PROPOSER_SEED: &[u8] = b"proposer";
+
+// Delegation limits
+pub const MAX_CATEGORY_DELEGATES: usize = 8;
+
+// Number of ProposalCategory variants, bounds per-category config
+pub const PROPOSAL_CATEGORY_COUNT: usize = 5;
+
+// Security council limits
+pub const MAX_COUNCIL_MEMBERS: usize = 9;
+
+#[program]
+pub mod governance {
+    use super::*;
+
+    // Events
+    #[event]
+    pub struct GovernanceInitialized {
+        pub sadl_mint: Pubkey,
+        pub emergency_admin: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct GovernanceConfigUpdated {
+        pub old_config: GovernanceConfig,
+        pub new_config: GovernanceConfig,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct VoteLockCreated {
+        pub owner: Pubkey,
+        pub amount: u64,
+        pub lock_end: i64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct VoteLockIncreased {
+        pub owner: Pubkey,
+        pub amount: u64,
+        pub total_amount: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct VoteLockExtended {
+        pub owner: Pubkey,
+        pub lock_end: i64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct VoteLockWithdrawn {
+        pub owner: Pubkey,
+        pub amount: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct VoterWeightRecorded {
+        pub proposal_id: u64,
+        pub voter: Pubkey,
+        pub weight: u64,
+        pub snapshot_time: i64,
+    }
+
+    #[event]
+    pub struct DelegateChanged {
+        pub delegator: Pubkey,
+        pub category: Option<ProposalCategory>,
+        pub previous_delegate: Option<Pubkey>,
+        pub new_delegate: Option<Pubkey>,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct ProposalCreated {
+        pub id: u64,
+        pub proposer: Pubkey,
+        pub title: String,
+        pub category: ProposalCategory,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct VoteCast {
+        pub proposal_id: u64,
+        pub voter: Pubkey,
+        pub vote_type: VoteType,
+        pub voting_power: u64,
+        pub counted_weight: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct VoteChanged {
+        pub proposal_id: u64,
+        pub voter: Pubkey,
+        pub previous_vote: Option<VoteType>,
+        pub new_vote: Option<VoteType>,
+        pub voting_power: u64,
+        pub counted_weight: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct ProposalFinalized {
+        pub id: u64,
+        pub status: ProposalStatus,
+        pub yes: u64,
+        pub no: u64,
+        pub abstain: u64,
+        pub total_voting_power: u64,
+        pub quorum_reached: bool,
+        pub winning_option: Option<u8>,
+        pub expires_at: Option<i64>,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct ProposalQueued {
+        pub id: u64,
+        pub executable_at: i64,
+        pub expires_at: i64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct ProposalCancelled {
+        pub id: u64,
+        pub proposer: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct DepositSettled {
+        pub proposal_id: u64,
+        pub proposer: Pubkey,
+        pub refunded: u64,
+        pub slashed: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct ProposalExpired {
+        pub id: u64,
+        pub previous_status: ProposalStatus,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct ProposalExecuted {
+        pub id: u64,
+        pub executor: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct EmergencyAction {
+        pub action_type: EmergencyActionType,
+        pub initiator: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct CouncilAction {
+        pub action_type: EmergencyActionType,
+        pub proposal_id: Option<u64>,
+        pub signers: Vec<Pubkey>,
+        pub timestamp: i64,
+    }
+
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        config: GovernanceConfig,
+        security_council: SecurityCouncil,
+    ) -> Result<()> {
+        require!(config.is_valid(), GovernanceError::InvalidConfig);
+        require!(security_council.is_valid(), GovernanceError::InvalidSecurityCouncil);
+
+        let governance = &mut ctx.accounts.governance;
+        governance.config = config;
+        governance.security_council = security_council;
+        governance.emergency_admin = ctx.accounts.admin.key();
+        governance.paused = false;
+        governance.active_proposal_count = 0;
+        governance.sadl_mint = ctx.accounts.sadl_mint.key();
+        governance.lock_vault_bump = ctx.bumps.lock_vault;
+        governance.deposit_vault_bump = ctx.bumps.deposit_vault;
+        governance.authority_bump = ctx.bumps.governance_authority;
+
+        emit!(GovernanceInitialized {
+            sadl_mint: governance.sadl_mint,
+            emergency_admin: governance.emergency_admin,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Only reachable through an executed proposal, signed by the governance PDA
+    pub fn update_governance_config(
+        ctx: Context<UpdateGovernanceConfig>,
+        config: GovernanceConfig,
+    ) -> Result<()> {
+        require!(config.is_valid(), GovernanceError::InvalidConfig);
+
+        let governance = &mut ctx.accounts.governance;
+        let old_config = std::mem::replace(&mut governance.config, config.clone());
+
+        emit!(GovernanceConfigUpdated {
+            old_config,
+            new_config: config,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Only reachable through an executed proposal, signed by the governance PDA
+    pub fn update_security_council(
+        ctx: Context<UpdateGovernanceConfig>,
+        security_council: SecurityCouncil,
+    ) -> Result<()> {
+        require!(security_council.is_valid(), GovernanceError::InvalidSecurityCouncil);
+
+        ctx.accounts.governance.security_council = security_council;
+
+        emit!(CouncilAction {
+            action_type: EmergencyActionType::UpdateSecurityCouncil,
+            proposal_id: None,
+            signers: vec![ctx.accounts.governance_authority.key()],
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_lock(ctx: Context<CreateLock>, amount: u64, lock_duration: i64) -> Result<()> {
+        require!(!ctx.accounts.governance.paused, GovernanceError::ContractPaused);
+        require!(amount > 0, GovernanceError::InvalidLockAmount);
+        require!(
+            (MIN_LOCK_DURATION..=MAX_LOCK_DURATION).contains(&lock_duration),
+            GovernanceError::InvalidLockDuration
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let vote_lock = &mut ctx.accounts.vote_lock;
+        vote_lock.governance = ctx.accounts.governance.key();
+        vote_lock.owner = ctx.accounts.owner.key();
+        vote_lock.amount = amount;
+        vote_lock.lock_start = current_time;
+        vote_lock.lock_end = current_time + lock_duration;
+        vote_lock.checkpoints = Vec::new();
+        vote_lock.bump = ctx.bumps.vote_lock;
+        vote_lock.checkpoint(current_time);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.owner_tokens.to_account_info(),
+                    to: ctx.accounts.lock_vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        emit!(VoteLockCreated {
+            owner: vote_lock.owner,
+            amount,
+            lock_end: vote_lock.lock_end,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn increase_lock(ctx: Context<UpdateLock>, amount: u64) -> Result<()> {
+        require!(!ctx.accounts.governance.paused, GovernanceError::ContractPaused);
+        require!(amount > 0, GovernanceError::InvalidLockAmount);
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let vote_lock = &mut ctx.accounts.vote_lock;
+        require!(
+            current_time < vote_lock.lock_end,
+            GovernanceError::LockExpired
+        );
+
+        vote_lock.amount += amount;
+        vote_lock.checkpoint(current_time);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.owner_tokens.to_account_info(),
+                    to: ctx.accounts.lock_vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        emit!(VoteLockIncreased {
+            owner: vote_lock.owner,
+            amount,
+            total_amount: vote_lock.amount,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn extend_lock(ctx: Context<UpdateLock>, new_lock_end: i64) -> Result<()> {
+        require!(!ctx.accounts.governance.paused, GovernanceError::ContractPaused);
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let vote_lock = &mut ctx.accounts.vote_lock;
+        require!(
+            current_time < vote_lock.lock_end,
+            GovernanceError::LockExpired
+        );
+        require!(
+            new_lock_end > vote_lock.lock_end && new_lock_end <= current_time + MAX_LOCK_DURATION,
+            GovernanceError::InvalidLockDuration
+        );
+
+        vote_lock.lock_end = new_lock_end;
+        vote_lock.checkpoint(current_time);
+
+        emit!(VoteLockExtended {
+            owner: vote_lock.owner,
+            lock_end: new_lock_end,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let vote_lock = &ctx.accounts.vote_lock;
+        require!(
+            current_time >= vote_lock.lock_end,
+            GovernanceError::LockNotExpired
+        );
+
+        let governance_key = ctx.accounts.governance.key();
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.lock_vault.to_account_info(),
+                    to: ctx.accounts.owner_tokens.to_account_info(),
+                    authority: ctx.accounts.lock_vault.to_account_info(),
+                },
+                &[&[
+                    LOCK_VAULT_SEED,
+                    governance_key.as_ref(),
+                    &[ctx.accounts.governance.lock_vault_bump],
+                ]],
+            ),
+            vote_lock.amount,
+        )?;
+
+        emit!(VoteLockWithdrawn {
+            owner: vote_lock.owner,
+            amount: vote_lock.amount,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn register_proposer(ctx: Context<RegisterProposer>) -> Result<()> {
+        let proposer_record = &mut ctx.accounts.proposer_record;
+        proposer_record.governance = ctx.accounts.governance.key();
+        proposer_record.proposer = ctx.accounts.proposer.key();
+        proposer_record.last_proposal_time = 0;
+        proposer_record.active_proposals = 0;
+        proposer_record.total_proposals = 0;
+        proposer_record.passed_proposals = 0;
+        proposer_record.bump = ctx.bumps.proposer_record;
+
+        Ok(())
+    }
+
+    pub fn create_proposal(ctx: Context<CreateProposal>, proposal: ProposalInput) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        require!(!governance.paused, GovernanceError::ContractPaused);
+
+        require!(
+            proposal.title.len() <= 128,
+            GovernanceError::TitleTooLong
+        );
+        require!(
+            proposal.description.len() <= 1024,
+            GovernanceError::DescriptionTooLong
+        );
+        match proposal.vote_mode {
+            VoteMode::Binary => require!(
+                proposal.options.is_empty(),
+                GovernanceError::InvalidProposalOptions
+            ),
+            _ => require!(
+                (2..=MAX_PROPOSAL_OPTIONS).contains(&proposal.options.len())
+                    && proposal.execution_plan.is_empty()
+                    && proposal.options.iter().all(|o| o.label.len() <= MAX_OPTION_LABEL_LEN),
+                GovernanceError::InvalidProposalOptions
+            ),
+        }
+
+        // Every plan's length prefix is accounted for separately in Proposal::LEN
+        let mut plan_bytes = proposal.execution_plan.try_to_vec()?.len() - 4;
+        for option in proposal.options.iter() {
+            plan_bytes += option.execution_plan.try_to_vec()?.len() - 4;
+        }
+        require!(
+            plan_bytes <= MAX_EXECUTION_PLAN_BYTES,
+            GovernanceError::ExecutionPlanTooLarge
+        );
+
+        // Rate limiting checks
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let voting_power = ctx.accounts.proposer_lock.voting_power(current_time);
+        require!(
+            voting_power >= governance.config.min_voting_power,
+            GovernanceError::InsufficientTokens
+        );
+
+        let proposer = ctx.accounts.proposer.key();
+        let proposer_record = &mut ctx.accounts.proposer_record;
+
+        if proposer_record.total_proposals > 0 {
+            require!(
+                current_time >= proposer_record.last_proposal_time + governance.config.proposal_cooldown,
+                GovernanceError::ProposalCooldownActive
+            );
+        }
+
+        require!(
+            governance.active_proposal_count < governance.config.max_concurrent_proposals,
+            GovernanceError::TooManyActiveProposals
+        );
+        require!(
+            proposer_record.active_proposals < governance.config.max_proposals_per_proposer,
+            GovernanceError::TooManyActiveProposals
+        );
+
+        let proposal_account = &mut ctx.accounts.proposal;
+        proposal_account.governance = governance.key();
+        proposal_account.id = proposal.id;
+        proposal_account.proposer = proposer;
+        proposal_account.title = proposal.title.clone();
+        proposal_account.description = proposal.description;
+        proposal_account.execution_plan = proposal.execution_plan;
+        proposal_account.category = proposal.category;
+        proposal_account.vote_mode = proposal.vote_mode;
+        proposal_account.voting_strategy = governance.config.strategy_for(proposal.category);
+        proposal_account.options = proposal.options
+            .into_iter()
+            .map(|o| ProposalOption {
+                label: o.label,
+                votes: 0,
+                vote_time: 0,
+                execution_plan: o.execution_plan,
+            })
+            .collect();
+        proposal_account.option_weight = 0;
+        proposal_account.winning_option = None;
+        proposal_account.votes = VoteCount::default();
+        proposal_account.vote_times = VoteTimes::default();
+        proposal_account.status = ProposalStatus::Active;
+        proposal_account.created_at = current_time;
+        proposal_account.voting_ends_at = current_time + governance.config.voting_period;
+        proposal_account.first_vote_at = None;
+        proposal_account.total_voting_power = 0;
+        proposal_account.abstain_voting_power = 0;
+        proposal_account.quorum_reached = false;
+        proposal_account.executable_at = 0;
+        proposal_account.expires_at = 0;
+        proposal_account.fast_tracked = false;
+        proposal_account.deposit = governance.config.proposal_deposit;
+        proposal_account.deposit_settled = false;
+        let deposit = proposal_account.deposit;
+
+        // Update governance state
+        governance.active_proposal_count += 1;
+        proposer_record.last_proposal_time = current_time;
+        proposer_record.active_proposals += 1;
+        proposer_record.total_proposals += 1;
+
+        // Escrowed until the outcome decides between refund and slashing
+        if deposit > 0 {
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.proposer_tokens.to_account_info(),
+                        to: ctx.accounts.deposit_vault.to_account_info(),
+                        authority: ctx.accounts.proposer.to_account_info(),
+                    },
+                ),
+                deposit,
+            )?;
+        }
+
+        emit!(ProposalCreated {
+            id: proposal.id,
+            proposer,
+            title: proposal.title.clone(),
+            category: proposal.category,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless: snapshots a voter's lock at proposal creation so the
+    // same tokens cannot be withdrawn, relocked elsewhere and voted again
+    pub fn create_voter_weight_record(ctx: Context<CreateVoterWeightRecord>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        let vote_lock = &ctx.accounts.vote_lock;
+
+        let record = &mut ctx.accounts.voter_weight_record;
+        record.proposal = proposal.key();
+        record.voter = vote_lock.owner;
+        record.snapshot_time = proposal.created_at;
+        record.weight = vote_lock.voting_power_at(proposal.created_at);
+        record.cast_by = None;
+        record.bump = ctx.bumps.voter_weight_record;
+
+        emit!(VoterWeightRecorded {
+            proposal_id: proposal.id,
+            voter: record.voter,
+            weight: record.weight,
+            snapshot_time: record.snapshot_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn vote(ctx: Context<Vote>, vote_type: VoteType) -> Result<()> {
+        let governance = &ctx.accounts.governance;
+        require!(!governance.paused, GovernanceError::ContractPaused);
+
+        let governance_key = governance.key();
+        let proposal = &mut ctx.accounts.proposal;
+        let proposal_key = proposal.key();
+        let voter = ctx.accounts.voter.key();
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            proposal.status == ProposalStatus::Active,
+            GovernanceError::ProposalNotActive
+        );
+        require!(
+            current_time <= proposal.voting_ends_at,
+            GovernanceError::VotingEnded
+        );
+        proposal.validate_vote(&vote_type)?;
+
+        // Double voting is prevented by the vote record PDA init
+        let mut voter_tokens: u64 = 0;
+        let mut counted_weight: u64 = 0;
+
+        // The voter's own snapshot weight, overriding a delegate who already cast it
+        if let Some(record) = ctx.accounts.voter_weight_record.as_mut() {
+            if let Some(delegate) = record.cast_by {
+                require!(delegate != voter, GovernanceError::AlreadyVoted);
+
+                let delegate_record = ctx.accounts.delegate_vote_record
+                    .as_mut()
+                    .filter(|r| r.voter == delegate)
+                    .ok_or(GovernanceError::MissingDelegateVoteRecord)?;
+                // Take back exactly what this lock contributed to the delegate's vote
+                let delegate_vote = delegate_record.vote_type.clone();
+                if let Some(delegate_vote) = delegate_vote.as_ref() {
+                    proposal.remove_votes(
+                        delegate_vote,
+                        delegate_record.weight,
+                        delegate_record.counted_weight,
+                        delegate_record.voted_at,
+                    );
+                }
+                delegate_record.weight -= record.weight;
+                delegate_record.counted_weight -= proposal.strategy_weight(record.weight);
+                if let Some(delegate_vote) = delegate_vote.as_ref() {
+                    proposal.add_votes(
+                        delegate_vote,
+                        delegate_record.weight,
+                        delegate_record.counted_weight,
+                        delegate_record.voted_at,
+                    );
+                }
+            }
+            record.cast_by = Some(voter);
+            voter_tokens += record.weight;
+            counted_weight += proposal.strategy_weight(record.weight);
+        }
+
+        // Delegated weight arrives as (delegation, voter_weight_record) pairs
+        require!(
+            ctx.remaining_accounts.len().is_multiple_of(2),
+            GovernanceError::InvalidDelegationAccounts
+        );
+        for pair in ctx.remaining_accounts.chunks(2) {
+            let delegation: Delegation = load_program_account(&pair[0])?;
+            let mut record: VoterWeightRecord = load_program_account(&pair[1])?;
+
+            require!(
+                delegation.governance == governance_key
+                    && delegation.delegator == record.voter
+                    && record.proposal == proposal_key,
+                GovernanceError::InvalidDelegationAccounts
+            );
+            require!(
+                delegation.delegate_for(proposal.category) == Some(voter),
+                GovernanceError::NotDelegate
+            );
+
+            // Delegators who voted directly (or through another delegate) keep their vote
+            if record.cast_by.is_some() {
+                continue;
+            }
+
+            record.cast_by = Some(voter);
+            voter_tokens += record.weight;
+            counted_weight += proposal.strategy_weight(record.weight);
+            store_program_account(&pair[1], &record)?;
+        }
+
+        require!(
+            voter_tokens > 0,
+            GovernanceError::NoVotingPower
+        );
+
+        proposal.add_votes(&vote_type, voter_tokens, counted_weight, current_time);
+        proposal.first_vote_at.get_or_insert(current_time);
+
+        let vote_record = &mut ctx.accounts.vote_record;
+        vote_record.proposal = proposal_key;
+        vote_record.voter = voter;
+        vote_record.vote_type = Some(vote_type.clone());
+        vote_record.weight = voter_tokens;
+        vote_record.counted_weight = counted_weight;
+        vote_record.voted_at = current_time;
+        vote_record.bump = ctx.bumps.vote_record;
+
+        emit!(VoteCast {
+            proposal_id: proposal.id,
+            voter,
+            vote_type,
+            voting_power: voter_tokens,
+            counted_weight,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless crank, anyone may tally a proposal once voting has closed
+    pub fn finalize(ctx: Context<Finalize>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            proposal.status == ProposalStatus::Active,
+            GovernanceError::ProposalNotActive
+        );
+        require!(
+            current_time > proposal.voting_ends_at,
+            GovernanceError::VotingNotEnded
+        );
+
+        finalize_proposal(
+            proposal,
+            &ctx.accounts.governance.config,
+            ctx.accounts.sadl_mint.supply,
+        )?;
+
+        let grace_period = ctx.accounts.governance.config.execution_grace_period;
+        let expires_at = if proposal.status == ProposalStatus::Passed && proposal.fast_tracked {
+            // Fast-tracked emergency proposals skip the timelock entirely
+            proposal.status = ProposalStatus::Queued;
+            proposal.executable_at = current_time;
+            proposal.expires_at = current_time + grace_period;
+            Some(proposal.expires_at)
+        } else if proposal.status == ProposalStatus::Passed {
+            // Must be queued within the grace period or it expires
+            proposal.expires_at = proposal.voting_ends_at + grace_period;
+            Some(proposal.expires_at)
+        } else {
+            // Passed proposals keep their slot until executed, vetoed or expired
+            ctx.accounts.governance.release_proposal_slot()?;
+            ctx.accounts.proposer_record.release_proposal_slot()?;
+            None
+        };
+        if expires_at.is_some() {
+            ctx.accounts.proposer_record.passed_proposals += 1;
+        }
+
+        emit!(ProposalFinalized {
+            id: proposal.id,
+            status: proposal.status.clone(),
+            yes: proposal.votes.yes,
+            no: proposal.votes.no,
+            abstain: proposal.votes.abstain,
+            total_voting_power: proposal.total_voting_power,
+            quorum_reached: proposal.quorum_reached,
+            winning_option: proposal.winning_option,
+            expires_at,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless crank, starts the timelock on a passed proposal
+    pub fn queue_proposal(ctx: Context<QueueProposal>) -> Result<()> {
+        let config = &ctx.accounts.governance.config;
+        let proposal = &mut ctx.accounts.proposal;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            proposal.status == ProposalStatus::Passed,
+            GovernanceError::ProposalNotPassed
+        );
+        require!(
+            current_time <= proposal.expires_at,
+            GovernanceError::ProposalExpired
+        );
+
+        proposal.status = ProposalStatus::Queued;
+        proposal.executable_at = current_time + config.execution_timelock;
+        proposal.expires_at = proposal.executable_at + config.execution_grace_period;
+
+        emit!(ProposalQueued {
+            id: proposal.id,
+            executable_at: proposal.executable_at,
+            expires_at: proposal.expires_at,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    // The proposer may withdraw a proposal until the first vote lands, even
+    // if that vote is later relinquished, forfeiting `cancel_slash_bps` of the deposit
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            proposal.status == ProposalStatus::Active,
+            GovernanceError::ProposalNotActive
+        );
+        require!(
+            proposal.first_vote_at.is_none(),
+            GovernanceError::ProposalHasVotes
+        );
+
+        proposal.status = ProposalStatus::Cancelled;
+        ctx.accounts.governance.release_proposal_slot()?;
+        ctx.accounts.proposer_record.release_proposal_slot()?;
+
+        emit!(ProposalCancelled {
+            id: proposal.id,
+            proposer: proposal.proposer,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless: refunds the deposit of a proposal that passed or reached
+    // quorum, or one that missed quorum without failing spectacularly,
+    // otherwise slashes it. Cancelled proposals forfeit `cancel_slash_bps`.
+    pub fn settle_deposit(ctx: Context<SettleDeposit>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        require!(
+            proposal.status != ProposalStatus::Active,
+            GovernanceError::ProposalStillActive
+        );
+        require!(!proposal.deposit_settled, GovernanceError::DepositAlreadySettled);
+
+        let config = &ctx.accounts.governance.config;
+        let deposit = proposal.deposit;
+        let slashed = match proposal.status {
+            // Withdrawn spam still costs the proposer part of the deposit
+            ProposalStatus::Cancelled => ((deposit as u128)
+                .checked_mul(config.cancel_slash_bps as u128)
+                .ok_or(GovernanceError::MathOverflow)?
+                / BPS_DENOMINATOR as u128) as u64,
+            ProposalStatus::Passed | ProposalStatus::Queued | ProposalStatus::Executed => 0,
+            _ if proposal.quorum_reached => 0,
+            _ if deposit_slashable(proposal, config, ctx.accounts.sadl_mint.supply)? => deposit,
+            _ => 0,
+        };
+        let refunded = deposit - slashed;
+
+        let governance_key = ctx.accounts.governance.key();
+        let transfers = [
+            (ctx.accounts.proposer_tokens.to_account_info(), refunded),
+            (ctx.accounts.slash_destination.to_account_info(), slashed),
+        ];
+        for (destination, amount) in transfers {
+            if amount == 0 {
+                continue;
+            }
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.deposit_vault.to_account_info(),
+                        to: destination,
+                        authority: ctx.accounts.deposit_vault.to_account_info(),
+                    },
+                    &[&[
+                        DEPOSIT_VAULT_SEED,
+                        governance_key.as_ref(),
+                        &[ctx.accounts.governance.deposit_vault_bump],
+                    ]],
+                ),
+                amount,
+            )?;
+        }
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.deposit_settled = true;
+
+        emit!(DepositSettled {
+            proposal_id: proposal.id,
+            proposer: proposal.proposer,
+            refunded,
+            slashed,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless crank for passed or queued proposals left past their grace period
+    pub fn expire_proposal(ctx: Context<ExpireProposal>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            proposal.status == ProposalStatus::Passed || proposal.status == ProposalStatus::Queued,
+            GovernanceError::ProposalNotPassed
+        );
+        require!(
+            current_time > proposal.expires_at,
+            GovernanceError::ProposalNotExpired
+        );
+
+        let previous_status = std::mem::replace(&mut proposal.status, ProposalStatus::Expired);
+        ctx.accounts.governance.release_proposal_slot()?;
+        ctx.accounts.proposer_record.release_proposal_slot()?;
+
+        emit!(ProposalExpired {
+            id: proposal.id,
+            previous_status,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    // Moves the recorded weight to a new choice, or back in after relinquishing
+    pub fn change_vote(ctx: Context<UpdateVote>, vote_type: VoteType) -> Result<()> {
+        require!(!ctx.accounts.governance.paused, GovernanceError::ContractPaused);
+
+        let proposal = &mut ctx.accounts.proposal;
+        let vote_record = &mut ctx.accounts.vote_record;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            proposal.status == ProposalStatus::Active,
+            GovernanceError::ProposalNotActive
+        );
+        require!(
+            current_time <= proposal.voting_ends_at,
+            GovernanceError::VotingEnded
+        );
+
+        proposal.validate_vote(&vote_type)?;
+
+        let previous_vote = vote_record.vote_type.take();
+        if let Some(previous) = previous_vote.as_ref() {
+            proposal.remove_votes(
+                previous,
+                vote_record.weight,
+                vote_record.counted_weight,
+                vote_record.voted_at,
+            );
+        }
+        // Changing a vote restarts its conviction
+        proposal.add_votes(&vote_type, vote_record.weight, vote_record.counted_weight, current_time);
+
+        vote_record.vote_type = Some(vote_type.clone());
+        vote_record.voted_at = current_time;
+
+        emit!(VoteChanged {
+            proposal_id: proposal.id,
+            voter: vote_record.voter,
+            previous_vote,
+            new_vote: Some(vote_type),
+            voting_power: vote_record.weight,
+            counted_weight: vote_record.counted_weight,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn relinquish_vote(ctx: Context<UpdateVote>) -> Result<()> {
+        require!(!ctx.accounts.governance.paused, GovernanceError::ContractPaused);
+
+        let proposal = &mut ctx.accounts.proposal;
+        let vote_record = &mut ctx.accounts.vote_record;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            proposal.status == ProposalStatus::Active,
+            GovernanceError::ProposalNotActive
+        );
+        require!(
+            current_time <= proposal.voting_ends_at,
+            GovernanceError::VotingEnded
+        );
+
+        // The record is kept so the voter cannot re-vote with a fresh snapshot
+        let previous_vote = vote_record.vote_type.take().ok_or(GovernanceError::VoteRelinquished)?;
+        proposal.remove_votes(
+            &previous_vote,
+            vote_record.weight,
+            vote_record.counted_weight,
+            vote_record.voted_at,
+        );
+
+        vote_record.voted_at = current_time;
+
+        emit!(VoteChanged {
+            proposal_id: proposal.id,
+            voter: vote_record.voter,
+            previous_vote: Some(previous_vote),
+            new_vote: None,
+            voting_power: vote_record.weight,
+            counted_weight: vote_record.counted_weight,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_delegation(ctx: Context<CreateDelegation>) -> Result<()> {
+        let delegation = &mut ctx.accounts.delegation;
+        delegation.governance = ctx.accounts.governance.key();
+        delegation.delegator = ctx.accounts.delegator.key();
+        delegation.delegate = None;
+        delegation.category_delegates = Vec::new();
+        delegation.bump = ctx.bumps.delegation;
+
+        Ok(())
+    }
+
+    // `category: None` sets the default delegate, `delegate: None` clears it
+    pub fn set_delegate(
+        ctx: Context<SetDelegate>,
+        category: Option<ProposalCategory>,
+        delegate: Option<Pubkey>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.governance.paused, GovernanceError::ContractPaused);
+
+        let delegation = &mut ctx.accounts.delegation;
+        require!(
+            delegate != Some(delegation.delegator),
+            GovernanceError::InvalidDelegate
+        );
+
+        let previous_delegate = match category {
+            None => std::mem::replace(&mut delegation.delegate, delegate),
+            Some(category) => {
+                let existing = delegation.category_delegates
+                    .iter()
+                    .position(|d| d.category == category);
+                let previous = existing.map(|i| delegation.category_delegates.remove(i).delegate);
+                if let Some(delegate) = delegate {
+                    require!(
+                        delegation.category_delegates.len() < MAX_CATEGORY_DELEGATES,
+                        GovernanceError::InvalidDelegate
+                    );
+                    delegation.category_delegates.push(CategoryDelegate { category, delegate });
+                }
+                previous
+            }
+        };
+
+        emit!(DelegateChanged {
+            delegator: delegation.delegator,
+            category,
+            previous_delegate,
+            new_delegate: delegate,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn execute(ctx: Context<Execute>) -> Result<()> {
+        let governance = &ctx.accounts.governance;
+        require!(!governance.paused, GovernanceError::ContractPaused);
+
+        let proposal = &mut ctx.accounts.proposal;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            proposal.status == ProposalStatus::Queued,
+            GovernanceError::ProposalNotQueued
+        );
+        require!(
+            current_time >= proposal.executable_at,
+            GovernanceError::TimelockNotExpired
+        );
+        require!(
+            current_time <= proposal.expires_at,
+            GovernanceError::ProposalExpired
+        );
+
+        proposal.status = ProposalStatus::Executed;
+        let proposal_id = proposal.id;
+        // Only the winning option's instructions run on multiple-choice proposals
+        let execution_plan = match proposal.winning_option {
+            Some(option) => proposal.options[option as usize].execution_plan.clone(),
+            None => proposal.execution_plan.clone(),
+        };
+
+        let governance = &mut ctx.accounts.governance;
+        governance.release_proposal_slot()?;
+        let governance_key = governance.key();
+        let authority_bump = governance.authority_bump;
+        ctx.accounts.proposer_record.release_proposal_slot()?;
+
+        // Persist state before the CPIs so a plan that re-enters governance
+        // sees the proposal as executed
+        ctx.accounts.proposal.exit(&crate::ID)?;
+        ctx.accounts.governance.exit(&crate::ID)?;
+
+        let signer_seeds: &[&[u8]] = &[
+            GOVERNANCE_AUTHORITY_SEED,
+            governance_key.as_ref(),
+            &[authority_bump],
+        ];
+        for instruction in execution_plan.iter() {
+            invoke_signed(
+                &instruction.to_instruction(),
+                ctx.remaining_accounts,
+                &[signer_seeds],
+            )?;
+        }
+
+        // Pick up any changes the plan made to governance's own accounts
+        ctx.accounts.governance.reload()?;
+        ctx.accounts.proposal.reload()?;
+
+        emit!(ProposalExecuted {
+            id: proposal_id,
+            executor: ctx.accounts.executor.key(),
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    // M-of-N council members, passed as signers in remaining_accounts, may
+    // veto a passed proposal at any point before it is executed
+    pub fn veto_proposal(ctx: Context<CouncilProposalAction>) -> Result<()> {
+        let signers = council_signers(&ctx.accounts.governance, ctx.remaining_accounts)?;
+        let proposal = &mut ctx.accounts.proposal;
+
+        require!(
+            proposal.status == ProposalStatus::Passed || proposal.status == ProposalStatus::Queued,
+            GovernanceError::ProposalNotPassed
+        );
+
+        proposal.status = ProposalStatus::Vetoed;
+        ctx.accounts.governance.release_proposal_slot()?;
+        ctx.accounts.proposer_record.release_proposal_slot()?;
+
+        emit!(CouncilAction {
+            action_type: EmergencyActionType::Veto,
+            proposal_id: Some(proposal.id),
+            signers,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Shortens voting on an emergency proposal, which then executes without a timelock
+    pub fn fast_track_proposal(ctx: Context<CouncilProposalAction>) -> Result<()> {
+        let signers = council_signers(&ctx.accounts.governance, ctx.remaining_accounts)?;
+        let emergency_voting_period = ctx.accounts.governance.config.emergency_voting_period;
+        let proposal = &mut ctx.accounts.proposal;
+
+        require!(
+            proposal.status == ProposalStatus::Active,
+            GovernanceError::ProposalNotActive
+        );
+        require!(
+            proposal.category == ProposalCategory::Emergency,
+            GovernanceError::NotEmergencyProposal
+        );
+        require!(!proposal.fast_tracked, GovernanceError::AlreadyFastTracked);
+
+        // Never end voting in the past, so every vote cast so far falls inside the period
+        let current_time = Clock::get()?.unix_timestamp;
+        proposal.fast_tracked = true;
+        proposal.voting_ends_at = proposal.voting_ends_at
+            .min(proposal.created_at + emergency_voting_period)
+            .max(current_time);
+
+        emit!(CouncilAction {
+            action_type: EmergencyActionType::FastTrack,
+            proposal_id: Some(proposal.id),
+            signers,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn pause(ctx: Context<EmergencyAction>) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        require!(
+            ctx.accounts.admin.key() == governance.emergency_admin,
+            GovernanceError::Unauthorized
+        );
+        
+        governance.paused = true;
+
+        emit!(EmergencyAction {
+            action_type: EmergencyActionType::Pause,
+            initiator: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn unpause(ctx: Context<EmergencyAction>) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        require!(
+            ctx.accounts.admin.key() == governance.emergency_admin,
+            GovernanceError::Unauthorized
+        );
+        
+        governance.paused = false;
+
+        emit!(EmergencyAction {
+            action_type: EmergencyActionType::Unpause,
+            initiator: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_emergency_admin(ctx: Context<EmergencyAction>, new_admin: Pubkey) -> Result<()> {
+        let governance = &mut ctx.accounts.governance;
+        require!(
+            ctx.accounts.admin.key() == governance.emergency_admin,
+            GovernanceError::Unauthorized
+        );
+        
+        governance.emergency_admin = new_admin;
+
+        emit!(EmergencyAction {
+            action_type: EmergencyActionType::UpdateAdmin,
+            initiator: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+fn finalize_proposal(
+    proposal: &mut Account<Proposal>,
+    config: &GovernanceConfig,
+    supply: u64,
+) -> Result<()> {
+    // Conviction depends on how long each vote was held, so it can only be
+    // applied once the voting period is final
+    if proposal.voting_strategy == VotingStrategy::Conviction {
+        proposal.apply_conviction()?;
+    }
+
+    // Quorum is a fraction of the current SADL supply
+    let quorum_votes = (supply as u128)
+        .checked_mul(config.quorum_bps as u128)
+        .ok_or(GovernanceError::MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+
+    let participating_votes = (proposal.total_voting_power - proposal.abstain_voting_power) as u128;
+    proposal.quorum_reached = turnout(proposal, config) >= quorum_votes;
+    if !proposal.quorum_reached || participating_votes == 0 {
+        proposal.status = ProposalStatus::Failed;
+        return Ok(());
+    }
+
+    // Multiple-choice proposals pass with a single, untied leading option whose
+    // share of the weight cast on options exceeds the approval threshold
+    if proposal.vote_mode != VoteMode::Binary {
+        let top_votes = proposal.options.iter().map(|o| o.votes).max().unwrap_or(0);
+        let mut leaders = proposal.options
+            .iter()
+            .enumerate()
+            .filter(|(_, o)| o.votes == top_votes);
+        let leader = match (leaders.next(), leaders.next()) {
+            (Some((index, _)), None) if top_votes > 0 => Some(index as u8),
+            _ => None,
+        };
+
+        let top_scaled = (top_votes as u128)
+            .checked_mul(BPS_DENOMINATOR as u128)
+            .ok_or(GovernanceError::MathOverflow)?;
+        let threshold_scaled = (proposal.option_weight as u128)
+            .checked_mul(config.approval_threshold_bps as u128)
+            .ok_or(GovernanceError::MathOverflow)?;
+        proposal.winning_option = leader.filter(|_| top_scaled > threshold_scaled);
+        proposal.status = if proposal.winning_option.is_some() {
+            ProposalStatus::Passed
+        } else {
+            ProposalStatus::Failed
+        };
+        return Ok(());
+    }
+
+    // Yes share of yes + no must strictly exceed the approval threshold
+    let decisive_votes = (proposal.votes.yes as u128)
+        .checked_add(proposal.votes.no as u128)
+        .ok_or(GovernanceError::MathOverflow)?;
+    let yes_scaled = (proposal.votes.yes as u128)
+        .checked_mul(BPS_DENOMINATOR as u128)
+        .ok_or(GovernanceError::MathOverflow)?;
+    let threshold_scaled = decisive_votes
+        .checked_mul(config.approval_threshold_bps as u128)
+        .ok_or(GovernanceError::MathOverflow)?;
+
+    proposal.status = if yes_scaled > threshold_scaled {
+        ProposalStatus::Passed
+    } else {
+        ProposalStatus::Failed
+    };
+
+    Ok(())
+}
+
+// Quorum counts raw participation, each voter once however their weight
+// was split, so the voting strategy only affects the outcome
+fn turnout(proposal: &Proposal, config: &GovernanceConfig) -> u128 {
+    if config.abstain_counts_toward_quorum {
+        proposal.total_voting_power as u128
+    } else {
+        (proposal.total_voting_power - proposal.abstain_voting_power) as u128
+    }
+}
+
+// Only asked of proposals that missed quorum. A spectacular failure is
+// turnout below the slash floor, or a no share of yes + no that reaches the
+// slash threshold; anything else simply fails and is refunded.
+fn deposit_slashable(proposal: &Proposal, config: &GovernanceConfig, supply: u64) -> Result<bool> {
+    let slash_floor = (supply as u128)
+        .checked_mul(config.slash_below_bps as u128)
+        .ok_or(GovernanceError::MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+    if turnout(proposal, config) < slash_floor {
+        return Ok(true);
+    }
+
+    let decisive_votes = (proposal.votes.yes as u128)
+        .checked_add(proposal.votes.no as u128)
+        .ok_or(GovernanceError::MathOverflow)?;
+    if decisive_votes == 0 {
+        return Ok(false);
+    }
+    let no_scaled = (proposal.votes.no as u128)
+        .checked_mul(BPS_DENOMINATOR as u128)
+        .ok_or(GovernanceError::MathOverflow)?;
+    let threshold_scaled = decisive_votes
+        .checked_mul(config.slash_threshold_bps as u128)
+        .ok_or(GovernanceError::MathOverflow)?;
+    Ok(no_scaled >= threshold_scaled)
+}
+
+fn integer_sqrt(value: u64) -> u64 {
+    let n = value as u128;
+    if n < 2 {
+        return value;
+    }
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x as u64
+}
+
+// Full weight for a max-length lock, decaying linearly to zero at unlock
+fn decayed_voting_power(amount: u64, lock_end: i64, timestamp: i64) -> u64 {
+    if timestamp >= lock_end {
+        return 0;
+    }
+    let remaining = (lock_end - timestamp).min(MAX_LOCK_DURATION) as u128;
+    (amount as u128 * remaining / MAX_LOCK_DURATION as u128) as u64
+}
+
+// Distinct council members that signed the transaction, at least the threshold
+fn council_signers(governance: &Governance, accounts: &[AccountInfo]) -> Result<Vec<Pubkey>> {
+    let council = &governance.security_council;
+    let mut signers: Vec<Pubkey> = Vec::new();
+    for account in accounts {
+        if account.is_signer
+            && council.members.contains(account.key)
+            && !signers.contains(account.key)
+        {
+            signers.push(*account.key);
+        }
+    }
+
+    require!(
+        council.threshold > 0 && signers.len() >= council.threshold as usize,
+        GovernanceError::InsufficientCouncilSigners
+    );
+
+    Ok(signers)
+}
+
+fn load_program_account<T: AccountDeserialize>(info: &AccountInfo) -> Result<T> {
+    require_keys_eq!(*info.owner, crate::ID, GovernanceError::InvalidDelegationAccounts);
+    let data = info.try_borrow_data()?;
+    T::try_deserialize(&mut &data[..])
+}
+
+fn store_program_account<T: AccountSerialize>(info: &AccountInfo, account: &T) -> Result<()> {
+    require!(info.is_writable, GovernanceError::InvalidDelegationAccounts);
+    let mut data = info.try_borrow_mut_data()?;
+    account.try_serialize(&mut &mut data[..])
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = admin, space = Governance::LEN)]
+    pub governance: Account<'info, Governance>,
+    /// CHECK: PDA that signs proposal execution plans, holds no data
+    #[account(seeds = [GOVERNANCE_AUTHORITY_SEED, governance.key().as_ref()], bump)]
+    pub governance_authority: UncheckedAccount<'info>,
+    pub sadl_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [LOCK_VAULT_SEED, governance.key().as_ref()],
+        bump,
+        token::mint = sadl_mint,
+        token::authority = lock_vault
+    )]
+    pub lock_vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [DEPOSIT_VAULT_SEED, governance.key().as_ref()],
+        bump,
+        token::mint = sadl_mint,
+        token::authority = deposit_vault
+    )]
+    pub deposit_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGovernanceConfig<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    #[account(
+        seeds = [GOVERNANCE_AUTHORITY_SEED, governance.key().as_ref()],
+        bump = governance.authority_bump
+    )]
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateLock<'info> {
+    pub governance: Account<'info, Governance>,
+    #[account(
+        init,
+        payer = owner,
+        space = VoteLock::LEN,
+        seeds = [VOTE_LOCK_SEED, governance.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub vote_lock: Account<'info, VoteLock>,
+    #[account(
+        mut,
+        seeds = [LOCK_VAULT_SEED, governance.key().as_ref()],
+        bump = governance.lock_vault_bump
+    )]
+    pub lock_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = owner_tokens.mint == governance.sadl_mint @ GovernanceError::InvalidMint
+    )]
+    pub owner_tokens: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLock<'info> {
+    pub governance: Account<'info, Governance>,
+    #[account(
+        mut,
+        has_one = governance,
+        has_one = owner,
+        seeds = [VOTE_LOCK_SEED, governance.key().as_ref(), owner.key().as_ref()],
+        bump = vote_lock.bump
+    )]
+    pub vote_lock: Account<'info, VoteLock>,
+    #[account(
+        mut,
+        seeds = [LOCK_VAULT_SEED, governance.key().as_ref()],
+        bump = governance.lock_vault_bump
+    )]
+    pub lock_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = owner_tokens.mint == governance.sadl_mint @ GovernanceError::InvalidMint
+    )]
+    pub owner_tokens: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    pub governance: Account<'info, Governance>,
+    #[account(
+        mut,
+        close = owner,
+        has_one = governance,
+        has_one = owner,
+        seeds = [VOTE_LOCK_SEED, governance.key().as_ref(), owner.key().as_ref()],
+        bump = vote_lock.bump
+    )]
+    pub vote_lock: Account<'info, VoteLock>,
+    #[account(
+        mut,
+        seeds = [LOCK_VAULT_SEED, governance.key().as_ref()],
+        bump = governance.lock_vault_bump
+    )]
+    pub lock_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = owner_tokens.owner == owner.key() @ GovernanceError::InvalidTokenOwner,
+        constraint = owner_tokens.mint == governance.sadl_mint @ GovernanceError::InvalidMint
+    )]
+    pub owner_tokens: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterProposer<'info> {
+    pub governance: Account<'info, Governance>,
+    #[account(
+        init,
+        payer = proposer,
+        space = ProposerRecord::LEN,
+        seeds = [PROPOSER_SEED, governance.key().as_ref(), proposer.key().as_ref()],
+        bump
+    )]
+    pub proposer_record: Account<'info, ProposerRecord>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    #[account(init, payer = proposer, space = Proposal::LEN)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    #[account(
+        seeds = [VOTE_LOCK_SEED, governance.key().as_ref(), proposer.key().as_ref()],
+        bump = proposer_lock.bump
+    )]
+    pub proposer_lock: Account<'info, VoteLock>,
+    #[account(
+        mut,
+        has_one = governance,
+        has_one = proposer,
+        seeds = [PROPOSER_SEED, governance.key().as_ref(), proposer.key().as_ref()],
+        bump = proposer_record.bump
+    )]
+    pub proposer_record: Account<'info, ProposerRecord>,
+    #[account(
+        mut,
+        constraint = proposer_tokens.mint == governance.sadl_mint @ GovernanceError::InvalidMint
+    )]
+    pub proposer_tokens: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [DEPOSIT_VAULT_SEED, governance.key().as_ref()],
+        bump = governance.deposit_vault_bump
+    )]
+    pub deposit_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVoterWeightRecord<'info> {
+    pub governance: Account<'info, Governance>,
+    #[account(has_one = governance)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        has_one = governance,
+        seeds = [VOTE_LOCK_SEED, governance.key().as_ref(), vote_lock.owner.as_ref()],
+        bump = vote_lock.bump
+    )]
+    pub vote_lock: Account<'info, VoteLock>,
+    #[account(
+        init,
+        payer = payer,
+        space = VoterWeightRecord::LEN,
+        seeds = [
+            VOTER_WEIGHT_SEED,
+            governance.key().as_ref(),
+            proposal.key().as_ref(),
+            vote_lock.owner.as_ref(),
+        ],
+        bump
+    )]
+    pub voter_weight_record: Account<'info, VoterWeightRecord>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Vote<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    #[account(mut, has_one = governance)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::LEN,
+        seeds = [VOTE_RECORD_SEED, proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    // Optional so that a delegate without a lock of its own can still vote
+    #[account(
+        mut,
+        has_one = proposal,
+        seeds = [
+            VOTER_WEIGHT_SEED,
+            governance.key().as_ref(),
+            proposal.key().as_ref(),
+            voter.key().as_ref(),
+        ],
+        bump = voter_weight_record.bump
+    )]
+    pub voter_weight_record: Option<Account<'info, VoterWeightRecord>>,
+    // Required when overriding a delegate that already voted the voter's weight
+    #[account(mut, has_one = proposal)]
+    pub delegate_vote_record: Option<Account<'info, VoteRecord>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Finalize<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    #[account(mut, has_one = governance)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        mut,
+        seeds = [PROPOSER_SEED, governance.key().as_ref(), proposal.proposer.as_ref()],
+        bump = proposer_record.bump
+    )]
+    pub proposer_record: Account<'info, ProposerRecord>,
+    #[account(address = governance.sadl_mint @ GovernanceError::InvalidMint)]
+    pub sadl_mint: Account<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct QueueProposal<'info> {
+    pub governance: Account<'info, Governance>,
+    #[account(mut, has_one = governance)]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    #[account(mut, has_one = governance, has_one = proposer)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        mut,
+        seeds = [PROPOSER_SEED, governance.key().as_ref(), proposal.proposer.as_ref()],
+        bump = proposer_record.bump
+    )]
+    pub proposer_record: Account<'info, ProposerRecord>,
+    pub proposer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettleDeposit<'info> {
+    pub governance: Account<'info, Governance>,
+    #[account(mut, has_one = governance)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(address = governance.sadl_mint @ GovernanceError::InvalidMint)]
+    pub sadl_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [DEPOSIT_VAULT_SEED, governance.key().as_ref()],
+        bump = governance.deposit_vault_bump
+    )]
+    pub deposit_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = proposer_tokens.owner == proposal.proposer @ GovernanceError::InvalidTokenOwner,
+        constraint = proposer_tokens.mint == governance.sadl_mint @ GovernanceError::InvalidMint
+    )]
+    pub proposer_tokens: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        address = governance.config.slash_destination @ GovernanceError::InvalidSlashDestination
+    )]
+    pub slash_destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireProposal<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    #[account(mut, has_one = governance)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        mut,
+        seeds = [PROPOSER_SEED, governance.key().as_ref(), proposal.proposer.as_ref()],
+        bump = proposer_record.bump
+    )]
+    pub proposer_record: Account<'info, ProposerRecord>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateVote<'info> {
+    pub governance: Account<'info, Governance>,
+    #[account(mut, has_one = governance)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        mut,
+        has_one = proposal,
+        has_one = voter,
+        seeds = [VOTE_RECORD_SEED, proposal.key().as_ref(), voter.key().as_ref()],
+        bump = vote_record.bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    pub voter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateDelegation<'info> {
+    pub governance: Account<'info, Governance>,
+    #[account(
+        init,
+        payer = delegator,
+        space = Delegation::LEN,
+        seeds = [DELEGATION_SEED, governance.key().as_ref(), delegator.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetDelegate<'info> {
+    pub governance: Account<'info, Governance>,
+    #[account(
+        mut,
+        has_one = governance,
+        has_one = delegator,
+        seeds = [DELEGATION_SEED, governance.key().as_ref(), delegator.key().as_ref()],
+        bump = delegation.bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+    pub delegator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Execute<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    #[account(mut, has_one = governance)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        mut,
+        seeds = [PROPOSER_SEED, governance.key().as_ref(), proposal.proposer.as_ref()],
+        bump = proposer_record.bump
+    )]
+    pub proposer_record: Account<'info, ProposerRecord>,
+    pub executor: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CouncilProposalAction<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    #[account(mut, has_one = governance)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        mut,
+        seeds = [PROPOSER_SEED, governance.key().as_ref(), proposal.proposer.as_ref()],
+        bump = proposer_record.bump
+    )]
+    pub proposer_record: Account<'info, ProposerRecord>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyAction<'info> {
+    #[account(mut)]
+    pub governance: Account<'info, Governance>,
+    pub admin: Signer<'info>,
+}
+
+#[account]
+pub struct Governance {
+    pub emergency_admin: Pubkey,
+    pub paused: bool,
+    pub active_proposal_count: u32,
+    pub sadl_mint: Pubkey,
+    pub lock_vault_bump: u8,
+    pub deposit_vault_bump: u8,
+    pub authority_bump: u8,
+    pub config: GovernanceConfig,
+    pub security_council: SecurityCouncil,
+}
+
+impl Governance {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // emergency_admin
+        1 + // paused
+        4 + // active_proposal_count
+        32 + // sadl_mint
+        1 + // lock_vault_bump
+        1 + // deposit_vault_bump
+        1 + // authority_bump
+        GovernanceConfig::LEN +
+        SecurityCouncil::LEN;
+
+    // Every terminal transition frees the proposal's concurrency slot
+    pub fn release_proposal_slot(&mut self) -> Result<()> {
+        self.active_proposal_count = self.active_proposal_count
+            .checked_sub(1)
+            .ok_or(GovernanceError::ProposalCountUnderflow)?;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct GovernanceConfig {
+    pub approval_threshold_bps: u16, // yes share of yes + no votes required to pass
+    pub quorum_bps: u16, // share of SADL supply that must vote
+    pub abstain_counts_toward_quorum: bool,
+    pub min_voting_power: u64, // needed to create a proposal
+    pub proposal_cooldown: i64, // between proposals from the same proposer
+    pub max_concurrent_proposals: u32,
+    pub max_proposals_per_proposer: u32, // active at once
+    pub voting_period: i64,
+    pub execution_timelock: i64, // between queueing and execution
+    pub execution_grace_period: i64, // to queue, and then to execute, before expiry
+    pub emergency_voting_period: i64, // for fast-tracked emergency proposals
+    pub category_strategies: Vec<CategoryStrategy>, // categories not listed vote linearly
+    pub proposal_deposit: u64, // SADL escrowed by the proposer
+    pub slash_threshold_bps: u16, // no share of yes + no at which the deposit is slashed
+    pub slash_below_bps: u16, // turnout, as a share of SADL supply, below which the deposit is slashed
+    pub cancel_slash_bps: u16, // share of the deposit slashed when the proposer cancels
+    pub slash_destination: Pubkey, // treasury SADL token account receiving slashed deposits
+}
+
+impl GovernanceConfig {
+    pub const LEN: usize = 2 + 2 + 1 + // thresholds
+        8 + 8 + 4 + 4 + // proposal rate limits
+        8 + 8 + 8 + // voting_period, execution_timelock, execution_grace_period
+        8 + // emergency_voting_period
+        4 + (CategoryStrategy::LEN * PROPOSAL_CATEGORY_COUNT) + // category_strategies
+        8 + 2 + 2 + 2 + 32; // proposal deposit and slashing
+
+    pub fn is_valid(&self) -> bool {
+        self.approval_threshold_bps as u64 <= BPS_DENOMINATOR
+            && self.quorum_bps as u64 <= BPS_DENOMINATOR
+            && self.proposal_cooldown >= 0
+            && self.max_concurrent_proposals > 0
+            && self.max_proposals_per_proposer > 0
+            && self.voting_period > 0
+            && self.execution_timelock >= 0
+            && self.execution_grace_period > 0
+            && self.emergency_voting_period > 0
+            && self.slash_threshold_bps > 0
+            && self.slash_threshold_bps as u64 <= BPS_DENOMINATOR
+            // A no share at the slash threshold must already fail the proposal
+            && self.slash_threshold_bps as u64 > BPS_DENOMINATOR - self.approval_threshold_bps as u64
+            && self.slash_below_bps <= self.quorum_bps
+            && self.cancel_slash_bps as u64 <= BPS_DENOMINATOR
+            && self.category_strategies.len() <= PROPOSAL_CATEGORY_COUNT
+            && self.category_strategies
+                .iter()
+                .enumerate()
+                .all(|(i, s)| self.category_strategies[..i].iter().all(|o| o.category != s.category))
+    }
+
+    pub fn strategy_for(&self, category: ProposalCategory) -> VotingStrategy {
+        self.category_strategies
+            .iter()
+            .find(|s| s.category == category)
+            .map(|s| s.strategy)
+            .unwrap_or(VotingStrategy::Linear)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CategoryStrategy {
+    pub category: ProposalCategory,
+    pub strategy: VotingStrategy,
+}
+
+impl CategoryStrategy {
+    pub const LEN: usize = 1 + 1;
+}
+
+// M-of-N keys that can veto passed proposals and fast-track emergencies,
+// a zero threshold disables the council
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SecurityCouncil {
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+impl SecurityCouncil {
+    pub const LEN: usize = 4 + (32 * MAX_COUNCIL_MEMBERS) + // members
+        1; // threshold
+
+    pub fn is_valid(&self) -> bool {
+        let mut members = self.members.clone();
+        members.sort();
+        members.dedup();
+        members.len() == self.members.len()
+            && self.members.len() <= MAX_COUNCIL_MEMBERS
+            && self.threshold as usize <= self.members.len()
+    }
+}
+
+// Per-proposer rate limiting and lifetime stats
+#[account]
+pub struct ProposerRecord {
+    pub governance: Pubkey,
+    pub proposer: Pubkey,
+    pub last_proposal_time: i64,
+    pub active_proposals: u32,
+    pub total_proposals: u64,
+    pub passed_proposals: u64,
+    pub bump: u8,
+}
+
+impl ProposerRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // governance
+        32 + // proposer
+        8 + // last_proposal_time
+        4 + // active_proposals
+        8 + // total_proposals
+        8 + // passed_proposals
+        1; // bump
+
+    pub fn release_proposal_slot(&mut self) -> Result<()> {
+        self.active_proposals = self.active_proposals
+            .checked_sub(1)
+            .ok_or(GovernanceError::ProposalCountUnderflow)?;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct VoteLock {
+    pub governance: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub lock_start: i64,
+    pub lock_end: i64,
+    pub checkpoints: Vec<LockCheckpoint>,
+    pub bump: u8,
+}
+
+impl VoteLock {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // governance
+        32 + // owner
+        8 + // amount
+        8 + // lock_start
+        8 + // lock_end
+        4 + (LockCheckpoint::LEN * MAX_LOCK_CHECKPOINTS) + // checkpoints
+        1; // bump
+
+    // Records the current lock state, keeping only the most recent history
+    pub fn checkpoint(&mut self, current_time: i64) {
+        if let Some(last) = self.checkpoints.last() {
+            if last.timestamp == current_time {
+                self.checkpoints.pop();
+            }
+        }
+        if self.checkpoints.len() >= MAX_LOCK_CHECKPOINTS {
+            self.checkpoints.remove(0);
+        }
+        self.checkpoints.push(LockCheckpoint {
+            timestamp: current_time,
+            amount: self.amount,
+            lock_end: self.lock_end,
+        });
+    }
+
+    // Voting power as of `timestamp`, zero if the lock did not exist yet
+    // or the snapshot predates the retained checkpoint history
+    pub fn voting_power_at(&self, timestamp: i64) -> u64 {
+        self.checkpoints
+            .iter()
+            .rev()
+            .find(|c| c.timestamp <= timestamp)
+            .map(|c| c.voting_power(timestamp))
+            .unwrap_or(0)
+    }
+
+    pub fn voting_power(&self, current_time: i64) -> u64 {
+        decayed_voting_power(self.amount, self.lock_end, current_time)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LockCheckpoint {
+    pub timestamp: i64,
+    pub amount: u64,
+    pub lock_end: i64,
+}
+
+impl LockCheckpoint {
+    pub const LEN: usize = 8 + 8 + 8;
+
+    pub fn voting_power(&self, timestamp: i64) -> u64 {
+        decayed_voting_power(self.amount, self.lock_end, timestamp)
+    }
+}
+
+#[account]
+pub struct VoterWeightRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub snapshot_time: i64,
+    pub cast_by: Option<Pubkey>, // the voter or the delegate who used this weight
+    pub bump: u8,
+}
+
+impl VoterWeightRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // proposal
+        32 + // voter
+        8 + // weight
+        8 + // snapshot_time
+        1 + 32 + // cast_by
+        1; // bump
+}
+
+#[account]
+pub struct Delegation {
+    pub governance: Pubkey,
+    pub delegator: Pubkey,
+    pub delegate: Option<Pubkey>,
+    pub category_delegates: Vec<CategoryDelegate>,
+    pub bump: u8,
+}
+
+impl Delegation {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // governance
+        32 + // delegator
+        1 + 32 + // delegate
+        4 + (CategoryDelegate::LEN * MAX_CATEGORY_DELEGATES) + // category_delegates
+        1; // bump
+
+    // A category-specific delegate takes precedence over the default one
+    pub fn delegate_for(&self, category: ProposalCategory) -> Option<Pubkey> {
+        self.category_delegates
+            .iter()
+            .find(|d| d.category == category)
+            .map(|d| d.delegate)
+            .or(self.delegate)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CategoryDelegate {
+    pub category: ProposalCategory,
+    pub delegate: Pubkey,
+}
+
+impl CategoryDelegate {
+    pub const LEN: usize = 1 + 32;
+}
+
+#[account]
+pub struct Proposal {
+    pub governance: Pubkey,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub title: String,
+    pub description: String,
+    pub execution_plan: Vec<ProposalInstruction>,
+    pub category: ProposalCategory,
+    pub vote_mode: VoteMode,
+    pub voting_strategy: VotingStrategy,
+    pub options: Vec<ProposalOption>, // empty for binary proposals
+    pub option_weight: u64, // weight of every option ballot, each voter counted once
+    pub winning_option: Option<u8>,
+    pub votes: VoteCount,
+    pub vote_times: VoteTimes,
+    pub status: ProposalStatus,
+    pub created_at: i64,
+    pub voting_ends_at: i64,
+    pub first_vote_at: Option<i64>, // blocks cancellation once set
+    pub total_voting_power: u64, // raw weight of everyone who voted, before the strategy
+    pub abstain_voting_power: u64,
+    pub quorum_reached: bool,
+    pub executable_at: i64,
+    pub expires_at: i64,
+    pub fast_tracked: bool,
+    pub deposit: u64,
+    pub deposit_settled: bool,
+}
+
+impl Proposal {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // governance
+        8 + // id
+        32 + // proposer
+        4 + 128 + // title
+        4 + 1024 + // description
+        4 + MAX_EXECUTION_PLAN_BYTES + // execution_plan, shared with option plans
+        1 + // category
+        1 + // vote_mode
+        1 + // voting_strategy
+        4 + (ProposalOption::LEN * MAX_PROPOSAL_OPTIONS) + // options
+        8 + // option_weight
+        1 + 1 + // winning_option
+        24 + // votes
+        VoteTimes::LEN +
+        1 + // status
+        8 + // created_at
+        8 + // voting_ends_at
+        1 + 8 + // first_vote_at
+        8 + // total_voting_power
+        8 + // abstain_voting_power
+        1 + // quorum_reached
+        8 + // executable_at
+        8 + // expires_at
+        1 + // fast_tracked
+        8 + // deposit
+        1; // deposit_settled
+
+    pub fn validate_vote(&self, vote_type: &VoteType) -> Result<()> {
+        let valid = match (self.vote_mode, vote_type) {
+            (_, VoteType::Abstain) => true,
+            (VoteMode::Binary, VoteType::Yes | VoteType::No) => true,
+            (VoteMode::Binary, VoteType::Options(_)) | (_, VoteType::Yes | VoteType::No) => false,
+            (mode, VoteType::Options(choices)) => {
+                let mut seen = vec![false; self.options.len()];
+                let in_range = choices.iter().all(|c| {
+                    let index = c.option as usize;
+                    index < seen.len() && !std::mem::replace(&mut seen[index], true)
+                });
+                let total_bps: u64 = choices.iter().map(|c| c.weight_bps as u64).sum();
+                in_range && match mode {
+                    VoteMode::SingleChoice => {
+                        choices.len() == 1 && total_bps == BPS_DENOMINATOR
+                    }
+                    VoteMode::Approval => {
+                        !choices.is_empty()
+                            && choices.iter().all(|c| c.weight_bps as u64 == BPS_DENOMINATOR)
+                    }
+                    VoteMode::WeightedSplit => {
+                        choices.iter().all(|c| c.weight_bps > 0) && total_bps == BPS_DENOMINATOR
+                    }
+                    VoteMode::Ranked => !choices.is_empty() && total_bps == 0,
+                    VoteMode::Binary => false,
+                }
+            }
+        };
+        require!(valid, GovernanceError::InvalidVote);
+        Ok(())
+    }
+
+    // Applied to each lock's snapshot weight before it is tallied, so
+    // delegating never changes how much a lock counts
+    pub fn strategy_weight(&self, weight: u64) -> u64 {
+        match self.voting_strategy {
+            VotingStrategy::Quadratic => integer_sqrt(weight),
+            VotingStrategy::Linear | VotingStrategy::Conviction => weight,
+        }
+    }
+
+    // Up to double weight for a vote held through the whole voting period,
+    // from the sum of weight x voted_at recorded for the tally
+    fn conviction_votes(&self, votes: u64, weighted_time: u128) -> Result<u64> {
+        let period = (self.voting_ends_at - self.created_at).max(1) as u128;
+        let held = (votes as u128)
+            .checked_mul(self.voting_ends_at as u128)
+            .and_then(|t| t.checked_sub(weighted_time))
+            .ok_or(GovernanceError::MathOverflow)?;
+        u64::try_from(votes as u128 + held / period).map_err(|_| GovernanceError::MathOverflow.into())
+    }
+
+    pub fn apply_conviction(&mut self) -> Result<()> {
+        self.votes.yes = self.conviction_votes(self.votes.yes, self.vote_times.yes)?;
+        self.votes.no = self.conviction_votes(self.votes.no, self.vote_times.no)?;
+        self.votes.abstain = self.conviction_votes(self.votes.abstain, self.vote_times.abstain)?;
+        self.option_weight = self.conviction_votes(self.option_weight, self.vote_times.option_weight)?;
+        for i in 0..self.options.len() {
+            self.options[i].votes =
+                self.conviction_votes(self.options[i].votes, self.options[i].vote_time)?;
+        }
+        Ok(())
+    }
+
+    // Ranked ballots are Borda-scored: the first of n options receives the full
+    // weight, the second (n - 1) / n of it, and so on
+    pub fn choice_share(&self, rank: usize, choice: &OptionChoice, weight: u64) -> u64 {
+        match self.vote_mode {
+            VoteMode::Ranked => {
+                let n = self.options.len() as u128;
+                (weight as u128 * (n - rank as u128) / n) as u64
+            }
+            _ => choice.share_of(weight),
+        }
+    }
+
+    pub fn add_votes(&mut self, vote_type: &VoteType, weight: u64, counted_weight: u64, voted_at: i64) {
+        let time = |votes: u64| votes as u128 * voted_at as u128;
+        match vote_type {
+            VoteType::Yes => {
+                self.votes.yes += counted_weight;
+                self.vote_times.yes += time(counted_weight);
+            }
+            VoteType::No => {
+                self.votes.no += counted_weight;
+                self.vote_times.no += time(counted_weight);
+            }
+            VoteType::Abstain => {
+                self.votes.abstain += counted_weight;
+                self.vote_times.abstain += time(counted_weight);
+                self.abstain_voting_power += weight;
+            }
+            VoteType::Options(choices) => {
+                for (rank, choice) in choices.iter().enumerate() {
+                    let share = self.choice_share(rank, choice, counted_weight);
+                    let option = &mut self.options[choice.option as usize];
+                    option.votes += share;
+                    option.vote_time += time(share);
+                }
+                self.option_weight += counted_weight;
+                self.vote_times.option_weight += time(counted_weight);
+            }
+        }
+        self.total_voting_power += weight;
+    }
+
+    pub fn remove_votes(&mut self, vote_type: &VoteType, weight: u64, counted_weight: u64, voted_at: i64) {
+        let time = |votes: u64| votes as u128 * voted_at as u128;
+        match vote_type {
+            VoteType::Yes => {
+                self.votes.yes -= counted_weight;
+                self.vote_times.yes -= time(counted_weight);
+            }
+            VoteType::No => {
+                self.votes.no -= counted_weight;
+                self.vote_times.no -= time(counted_weight);
+            }
+            VoteType::Abstain => {
+                self.votes.abstain -= counted_weight;
+                self.vote_times.abstain -= time(counted_weight);
+                self.abstain_voting_power -= weight;
+            }
+            VoteType::Options(choices) => {
+                for (rank, choice) in choices.iter().enumerate() {
+                    let share = self.choice_share(rank, choice, counted_weight);
+                    let option = &mut self.options[choice.option as usize];
+                    option.votes -= share;
+                    option.vote_time -= time(share);
+                }
+                self.option_weight -= counted_weight;
+                self.vote_times.option_weight -= time(counted_weight);
+            }
+        }
+        self.total_voting_power -= weight;
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProposalOption {
+    pub label: String,
+    pub votes: u64,
+    pub vote_time: u128, // sum of votes x voted_at, for conviction
+    pub execution_plan: Vec<ProposalInstruction>, // runs only if this option wins
+}
+
+impl ProposalOption {
+    pub const LEN: usize = 4 + MAX_OPTION_LABEL_LEN + // label
+        8 + // votes
+        16 + // vote_time
+        4; // execution_plan, contents counted in MAX_EXECUTION_PLAN_BYTES
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ProposalOptionInput {
+    pub label: String,
+    pub execution_plan: Vec<ProposalInstruction>,
+}
+
+// An instruction invoked by `execute`, signed by the governance authority PDA
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProposalInstruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<ProposalAccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl ProposalInstruction {
+    pub fn to_instruction(&self) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: self.accounts
+                .iter()
+                .map(|meta| AccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProposalAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[account]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub vote_type: Option<VoteType>, // None once relinquished
+    pub weight: u64, // own weight plus weight delegated to this voter
+    pub counted_weight: u64, // sum of each lock's weight after the proposal's voting strategy
+    pub voted_at: i64,
+    pub bump: u8,
+}
+
+impl VoteRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // proposal
+        32 + // voter
+        1 + 1 + 4 + (OptionChoice::LEN * MAX_PROPOSAL_OPTIONS) + // vote_type
+        8 + // weight
+        8 + // counted_weight
+        8 + // voted_at
+        1; // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct VoteCount {
+    pub yes: u64,
+    pub no: u64,
+    pub abstain: u64,
+}
+
+// Sum of counted weight x voted_at per tally, so conviction can be measured
+// at finalize against the final voting period
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct VoteTimes {
+    pub yes: u128,
+    pub no: u128,
+    pub abstain: u128,
+    pub option_weight: u128,
+}
+
+impl VoteTimes {
+    pub const LEN: usize = 16 * 4;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Clone)]
+pub enum ProposalStatus {
+    Active,
+    Passed,
+    Failed,
+    Executed,
+    Queued,
+    Cancelled,
+    Vetoed,
+    Expired,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Clone, Copy)]
+pub enum ProposalCategory {
+    General,
+    Treasury,
+    Grants,
+    Parameters,
+    Emergency,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum VoteType {
+    Yes,
+    No,
+    Abstain,
+    Options(Vec<OptionChoice>), // multiple-choice proposals only
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct OptionChoice {
+    pub option: u8,
+    pub weight_bps: u16, // share of the voter's weight given to this option, zero when ranked
+}
+
+impl OptionChoice {
+    pub const LEN: usize = 1 + 2;
+
+    pub fn share_of(&self, weight: u64) -> u64 {
+        (weight as u128 * self.weight_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Clone, Copy)]
+pub enum VotingStrategy {
+    Linear,
+    Quadratic, // integer square root of each lock's weight
+    Conviction, // weight grows with how long the vote was held, measured at finalize
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Clone, Copy)]
+pub enum VoteMode {
+    Binary, // yes / no / abstain
+    SingleChoice, // all weight to one option
+    Approval, // full weight to every approved option
+    WeightedSplit, // weight split across options in basis points
+    Ranked, // options in order of preference, Borda-scored
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ProposalInput {
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+    pub execution_plan: Vec<ProposalInstruction>,
+    pub category: ProposalCategory,
+    pub vote_mode: VoteMode,
+    pub options: Vec<ProposalOptionInput>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub enum EmergencyActionType {
+    Pause,
+    Unpause,
+    UpdateAdmin,
+    Veto,
+    FastTrack,
+    UpdateSecurityCouncil,
+}
+
+#[error_code]
+pub enum GovernanceError {
+    #[msg("Title exceeds maximum length")]
+    TitleTooLong,
+    #[msg("Description exceeds maximum length")]
+    DescriptionTooLong,
+    #[msg("Insufficient tokens to create proposal")]
+    InsufficientTokens,
+    #[msg("Proposal is not active")]
+    ProposalNotActive,
+    #[msg("Voting period has ended")]
+    VotingEnded,
+    #[msg("No voting power")]
+    NoVotingPower,
+    #[msg("Proposal has not passed")]
+    ProposalNotPassed,
+    #[msg("Timelock period has not expired")]
+    TimelockNotExpired,
+    #[msg("Contract is paused")]
+    ContractPaused,
+    #[msg("Proposal cooldown period active")]
+    ProposalCooldownActive,
+    #[msg("Too many active proposals")]
+    TooManyActiveProposals,
+    #[msg("Already voted on this proposal")]
+    AlreadyVoted,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid lock amount")]
+    InvalidLockAmount,
+    #[msg("Invalid lock duration")]
+    InvalidLockDuration,
+    #[msg("Lock has expired")]
+    LockExpired,
+    #[msg("Lock has not expired")]
+    LockNotExpired,
+    #[msg("Token account mint does not match SADL mint")]
+    InvalidMint,
+    #[msg("Token account is not owned by the lock owner")]
+    InvalidTokenOwner,
+    #[msg("Invalid delegate")]
+    InvalidDelegate,
+    #[msg("Invalid delegation accounts")]
+    InvalidDelegationAccounts,
+    #[msg("Voter is not the delegate for this proposal")]
+    NotDelegate,
+    #[msg("Execution plan exceeds maximum size")]
+    ExecutionPlanTooLarge,
+    #[msg("Delegate vote record required to override a delegated vote")]
+    MissingDelegateVoteRecord,
+    #[msg("Vote has already been relinquished")]
+    VoteRelinquished,
+    #[msg("Voting period has not ended")]
+    VotingNotEnded,
+    #[msg("Invalid governance config")]
+    InvalidConfig,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Proposal already has votes")]
+    ProposalHasVotes,
+    #[msg("Proposal has not been queued")]
+    ProposalNotQueued,
+    #[msg("Proposal has expired")]
+    ProposalExpired,
+    #[msg("Proposal has not expired")]
+    ProposalNotExpired,
+    #[msg("Invalid security council")]
+    InvalidSecurityCouncil,
+    #[msg("Not enough security council signers")]
+    InsufficientCouncilSigners,
+    #[msg("Only emergency proposals can be fast-tracked")]
+    NotEmergencyProposal,
+    #[msg("Proposal is already fast-tracked")]
+    AlreadyFastTracked,
+    #[msg("Invalid proposal options")]
+    InvalidProposalOptions,
+    #[msg("Vote does not match the proposal's voting mode")]
+    InvalidVote,
+    #[msg("Proposal is still active")]
+    ProposalStillActive,
+    #[msg("Deposit has already been settled")]
+    DepositAlreadySettled,
+    #[msg("Slash destination does not match governance config")]
+    InvalidSlashDestination,
+    #[msg("Active proposal count underflow")]
+    ProposalCountUnderflow,
+}
@@ -1,424 +1,1978 @@
-use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token as SplToken, TokenAccount};
-
-declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
-
-// Constants for rate limiting and supply caps
-pub const MAX_SUPPLY: u64 = 1_000_000_000 * 10u64.pow(9); // 1 billion tokens with 9 decimals
-pub const MAX_DISTRIBUTION_RATE: u64 = MAX_SUPPLY / 100; // 1% of total supply per distribution
-pub const DISTRIBUTION_COOLDOWN: i64 = 3600; // 1 hour cooldown between distributions
-
-#[program]
-pub mod sadl_token {
-    use super::*;
-
-    // Events
-    #[event]
-    pub struct TokenInitialized {
-        pub supply: u64,
-        pub authority: Pubkey,
-        pub timestamp: i64,
-    }
-
-    #[event]
-    pub struct Distribution {
-        pub pool_type: PoolType,
-        pub amount: u64,
-        pub recipient: Pubkey,
-        pub timestamp: i64,
-    }
-
-    #[event]
-    pub struct EmergencyAction {
-        pub action_type: EmergencyActionType,
-        pub initiator: Pubkey,
-        pub timestamp: i64,
-    }
-
-    pub fn initialize(ctx: Context<Initialize>, config: TokenConfig) -> Result<()> {
-        require!(
-            config.supply <= MAX_SUPPLY,
-            DistributionError::SupplyCapExceeded
-        );
-
-        let token = &mut ctx.accounts.token;
-        token.supply = config.supply;
-        token.decimals = config.decimals;
-        token.authority = config.authority;
-        token.emergency_admin = config.authority; // Initially same as authority
-        token.total_distributed = 0;
-        token.paused = false;
-        token.last_distribution = 0;
-        token.distribution_pools = DistributionPools {
-            community: Pool {
-                allocation: (config.supply * 30) / 100,  // 30%
-                distributed: 0,
-                start_time: config.distribution_start,
-                end_time: None,
-            },
-            development: Pool {
-                allocation: (config.supply * 25) / 100,  // 25%
-                distributed: 0,
-                start_time: config.distribution_start,
-                end_time: None,
-            },
-            treasury: Pool {
-                allocation: (config.supply * 20) / 100,  // 20%
-                distributed: 0,
-                start_time: config.distribution_start,
-                end_time: None,
-            },
-            agent_incentives: Pool {
-                allocation: (config.supply * 15) / 100,  // 15%
-                distributed: 0,
-                start_time: config.distribution_start,
-                end_time: None,
-            },
-            team: Pool {
-                allocation: (config.supply * 10) / 100,  // 10%
-                distributed: 0,
-                start_time: config.distribution_start,
-                end_time: Some(config.distribution_start + 63072000), // 2 years
-            },
-        };
-
-        // Initialize mint
-        token::mint_to(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                token::MintTo {
-                    mint: ctx.accounts.mint.to_account_info(),
-                    to: ctx.accounts.vault.to_account_info(),
-                    authority: ctx.accounts.authority.to_account_info(),
-                },
-                &[&[&token.authority.to_bytes()[..], &[token.bump]]],
-            ),
-            config.supply,
-        )?;
-
-        // Emit initialization event
-        emit!(TokenInitialized {
-            supply: config.supply,
-            authority: config.authority,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-
-        Ok(())
-    }
-
-    pub fn distribute(ctx: Context<Distribute>, pool_type: PoolType, amount: u64) -> Result<()> {
-        let token = &mut ctx.accounts.token;
-        
-        // Check if contract is paused
-        require!(!token.paused, DistributionError::ContractPaused);
-
-        // Rate limiting checks
-        let current_time = Clock::get()?.unix_timestamp;
-        require!(
-            current_time >= token.last_distribution + DISTRIBUTION_COOLDOWN,
-            DistributionError::CooldownNotElapsed
-        );
-        require!(
-            amount <= MAX_DISTRIBUTION_RATE,
-            DistributionError::RateLimitExceeded
-        );
-
-        let pool = token.distribution_pools.get_pool_mut(pool_type);
-
-        // Validate distribution
-        require!(
-            amount <= pool.allocation - pool.distributed,
-            DistributionError::ExceedsAllocation
-        );
-        require!(
-            current_time >= pool.start_time,
-            DistributionError::DistributionNotStarted
-        );
-        if let Some(end_time) = pool.end_time {
-            require!(
-                current_time <= end_time,
-                DistributionError::DistributionEnded
-            );
-        }
-
-        // Update distribution state
-        pool.distributed += amount;
-        token.total_distributed += amount;
-        token.last_distribution = current_time;
-
-        // Transfer tokens
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                token::Transfer {
-                    from: ctx.accounts.vault.to_account_info(),
-                    to: ctx.accounts.recipient.to_account_info(),
-                    authority: ctx.accounts.authority.to_account_info(),
-                },
-                &[&[&token.authority.to_bytes()[..], &[token.bump]]],
-            ),
-            amount,
-        )?;
-
-        // Emit distribution event
-        emit!(Distribution {
-            pool_type,
-            amount,
-            recipient: ctx.accounts.recipient.key(),
-            timestamp: current_time,
-        });
-
-        Ok(())
-    }
-
-    pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
-        let token = &ctx.accounts.token;
-        require!(!token.paused, DistributionError::ContractPaused);
-
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                token::Transfer {
-                    from: ctx.accounts.from.to_account_info(),
-                    to: ctx.accounts.to.to_account_info(),
-                    authority: ctx.accounts.authority.to_account_info(),
-                },
-            ),
-            amount,
-        )?;
-        Ok(())
-    }
-
-    pub fn delegate(ctx: Context<Delegate>, amount: u64) -> Result<()> {
-        let token = &ctx.accounts.token;
-        require!(!token.paused, DistributionError::ContractPaused);
-
-        token::approve(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                token::Approve {
-                    to: ctx.accounts.delegate_account.to_account_info(),
-                    delegate: ctx.accounts.delegate.to_account_info(),
-                    authority: ctx.accounts.authority.to_account_info(),
-                },
-            ),
-            amount,
-        )?;
-        Ok(())
-    }
-
-    pub fn pause(ctx: Context<EmergencyAction>) -> Result<()> {
-        let token = &mut ctx.accounts.token;
-        require!(
-            ctx.accounts.admin.key() == token.emergency_admin,
-            DistributionError::Unauthorized
-        );
-        
-        token.paused = true;
-
-        emit!(EmergencyAction {
-            action_type: EmergencyActionType::Pause,
-            initiator: ctx.accounts.admin.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-
-        Ok(())
-    }
-
-    pub fn unpause(ctx: Context<EmergencyAction>) -> Result<()> {
-        let token = &mut ctx.accounts.token;
-        require!(
-            ctx.accounts.admin.key() == token.emergency_admin,
-            DistributionError::Unauthorized
-        );
-        
-        token.paused = false;
-
-        emit!(EmergencyAction {
-            action_type: EmergencyActionType::Unpause,
-            initiator: ctx.accounts.admin.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-
-        Ok(())
-    }
-
-    pub fn update_emergency_admin(ctx: Context<EmergencyAction>, new_admin: Pubkey) -> Result<()> {
-        let token = &mut ctx.accounts.token;
-        require!(
-            ctx.accounts.admin.key() == token.emergency_admin,
-            DistributionError::Unauthorized
-        );
-        
-        token.emergency_admin = new_admin;
-
-        emit!(EmergencyAction {
-            action_type: EmergencyActionType::UpdateAdmin,
-            initiator: ctx.accounts.admin.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-
-        Ok(())
-    }
-}
-
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(init, payer = authority, space = 8 + Token::LEN)]
-    pub token: Account<'info, Token>,
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub token_program: Program<'info, SplToken>,
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-pub struct Distribute<'info> {
-    #[account(mut)]
-    pub token: Account<'info, Token>,
-    #[account(mut)]
-    pub vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub recipient: Account<'info, TokenAccount>,
-    pub authority: Signer<'info>,
-    pub token_program: Program<'info, SplToken>,
-}
-
-#[derive(Accounts)]
-pub struct Transfer<'info> {
-    #[account(mut)]
-    pub token: Account<'info, Token>,
-    #[account(mut)]
-    pub from: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
-    pub authority: Signer<'info>,
-    pub token_program: Program<'info, SplToken>,
-}
-
-#[derive(Accounts)]
-pub struct Delegate<'info> {
-    #[account(mut)]
-    pub token: Account<'info, Token>,
-    #[account(mut)]
-    pub delegate_account: Account<'info, TokenAccount>,
-    pub delegate: AccountInfo<'info>,
-    pub authority: Signer<'info>,
-    pub token_program: Program<'info, SplToken>,
-}
-
-#[derive(Accounts)]
-pub struct EmergencyAction<'info> {
-    #[account(mut)]
-    pub token: Account<'info, Token>,
-    pub admin: Signer<'info>,
-}
-
-#[account]
-pub struct Token {
-    pub supply: u64,
-    pub decimals: u8,
-    pub authority: Pubkey,
-    pub bump: u8,
-    pub total_distributed: u64,
-    pub distribution_pools: DistributionPools,
-    pub paused: bool,
-    pub last_distribution: i64,
-    pub emergency_admin: Pubkey,
-}
-
-impl Token {
-    pub const LEN: usize = 8 + // discriminator
-        8 + // supply
-        1 + // decimals
-        32 + // authority
-        1 + // bump
-        8 + // total_distributed
-        DistributionPools::LEN +
-        1 + // paused
-        8 + // last_distribution
-        32; // emergency_admin
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct DistributionPools {
-    pub community: Pool,
-    pub development: Pool,
-    pub treasury: Pool,
-    pub agent_incentives: Pool,
-    pub team: Pool,
-}
-
-impl DistributionPools {
-    pub const LEN: usize = Pool::LEN * 5;
-
-    pub fn get_pool_mut(&mut self, pool_type: PoolType) -> &mut Pool {
-        match pool_type {
-            PoolType::Community => &mut self.community,
-            PoolType::Development => &mut self.development,
-            PoolType::Treasury => &mut self.treasury,
-            PoolType::AgentIncentives => &mut self.agent_incentives,
-            PoolType::Team => &mut self.team,
-        }
-    }
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct Pool {
-    pub allocation: u64,
-    pub distributed: u64,
-    pub start_time: i64,
-    pub end_time: Option<i64>,
-}
-
-impl Pool {
-    pub const LEN: usize = 8 + 8 + 8 + 9;
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
-pub enum PoolType {
-    Community,
-    Development,
-    Treasury,
-    AgentIncentives,
-    Team,
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize)]
-pub struct TokenConfig {
-    pub supply: u64,
-    pub decimals: u8,
-    pub authority: Pubkey,
-    pub distribution_start: i64,
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize)]
-pub enum EmergencyActionType {
-    Pause,
-    Unpause,
-    UpdateAdmin,
-}
-
-#[error_code]
-pub enum DistributionError {
-    #[msg("Distribution amount exceeds pool allocation")]
-    ExceedsAllocation,
-    #[msg("Distribution period has not started")]
-    DistributionNotStarted,
-    #[msg("Distribution period has ended")]
-    DistributionEnded,
-    #[msg("Contract is paused")]
-    ContractPaused,
-    #[msg("Distribution rate limit exceeded")]
-    RateLimitExceeded,
-    #[msg("Distribution cooldown period not elapsed")]
-    CooldownNotElapsed,
-    #[msg("Supply cap exceeded")]
-    SupplyCapExceeded,
-    #[msg("Unauthorized")]
-    Unauthorized,
-}
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_spl::token::{self, Mint, Token as SplToken, TokenAccount};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+// Constants for rate limiting and supply caps
+pub const MAX_SUPPLY: u64 = 1_000_000_000 * 10u64.pow(9); // 1 billion tokens with 9 decimals
+// Default per-pool limits, each pool can be retuned by governance via update_pool_limits
+pub const MAX_DISTRIBUTION_RATE: u64 = MAX_SUPPLY / 100; // 1% of total supply per window
+pub const DISTRIBUTION_WINDOW: i64 = 3600; // 1 hour rate limit window
+pub const DISTRIBUTION_COOLDOWN: i64 = 3600; // 1 hour cooldown between distributions
+
+// PDA seeds
+pub const TOKEN_SEED: &[u8] = b"token";
+pub const MINT_SEED: &[u8] = b"mint";
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const VESTING_SEED: &[u8] = b"vesting";
+pub const MERKLE_DISTRIBUTOR_SEED: &[u8] = b"merkle_distributor";
+pub const CLAIM_BITMAP_SEED: &[u8] = b"claim_bitmap";
+pub const POOL_ROLES_SEED: &[u8] = b"pool_roles";
+pub const STAKE_POOL_SEED: &[u8] = b"stake_pool";
+pub const STAKE_VAULT_SEED: &[u8] = b"stake_vault";
+pub const STAKE_POSITION_SEED: &[u8] = b"stake_position";
+
+// Role limits
+pub const MAX_POOL_DISTRIBUTORS: usize = 10;
+
+// Staking
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_STAKE_LOCK_DURATION: i64 = 31_536_000; // 1 year
+pub const MAX_LOCK_MULTIPLIER_BPS: u64 = 20_000; // 2x weight at the maximum lock
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+// Airdrop claim tracking
+pub const CLAIM_BITMAP_BYTES: usize = 1024;
+pub const CLAIMS_PER_BITMAP_PAGE: u64 = (CLAIM_BITMAP_BYTES * 8) as u64; // 8192 claims per page
+
+#[program]
+pub mod sadl_token {
+    use super::*;
+
+    // Events
+    #[event]
+    pub struct TokenInitialized {
+        pub supply: u64,
+        pub authority: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct Distribution {
+        pub pool_type: PoolType,
+        pub amount: u64,
+        pub recipient: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct DistributorAdded {
+        pub pool_type: PoolType,
+        pub distributor: Pubkey,
+        pub authority: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct DistributorRemoved {
+        pub pool_type: PoolType,
+        pub distributor: Pubkey,
+        pub authority: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct PoolLimitsUpdated {
+        pub pool_type: PoolType,
+        pub old_limits: PoolLimits,
+        pub new_limits: PoolLimits,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct VestingScheduleCreated {
+        pub beneficiary: Pubkey,
+        pub pool_type: PoolType,
+        pub grant_id: u64,
+        pub total_amount: u64,
+        pub cliff_time: i64,
+        pub end_time: i64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct VestedTokensClaimed {
+        pub beneficiary: Pubkey,
+        pub pool_type: PoolType,
+        pub grant_id: u64,
+        pub amount: u64,
+        pub total_claimed: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct MerkleDistributorCreated {
+        pub distributor: Pubkey,
+        pub campaign_id: u64,
+        pub merkle_root: [u8; 32],
+        pub total_amount: u64,
+        pub num_claims: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct AirdropClaimed {
+        pub distributor: Pubkey,
+        pub index: u64,
+        pub claimant: Pubkey,
+        pub amount: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct StakePoolInitialized {
+        pub stake_pool: Pubkey,
+        pub reward_pool_type: PoolType,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct StakeRewardsFunded {
+        pub stake_pool: Pubkey,
+        pub reward_pool_type: PoolType,
+        pub amount: u64,
+        pub reward_rate: u64,
+        pub reward_end_time: i64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct Staked {
+        pub owner: Pubkey,
+        pub amount: u64,
+        pub lock_end: i64,
+        pub multiplier_bps: u64,
+        pub total_staked: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct Unstaked {
+        pub owner: Pubkey,
+        pub amount: u64,
+        pub total_staked: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct StakeLockExpired {
+        pub owner: Pubkey,
+        pub weighted_amount: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct StakeRewardsClaimed {
+        pub owner: Pubkey,
+        pub amount: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct EmergencyAction {
+        pub action_type: EmergencyActionType,
+        pub initiator: Pubkey,
+        pub timestamp: i64,
+    }
+
+    pub fn initialize(ctx: Context<Initialize>, config: TokenConfig) -> Result<()> {
+        require!(
+            config.supply <= MAX_SUPPLY,
+            DistributionError::SupplyCapExceeded
+        );
+
+        let token = &mut ctx.accounts.token;
+        token.supply = config.supply;
+        token.decimals = config.decimals;
+        token.authority = config.authority;
+        token.emergency_admin = config.authority; // Initially same as authority
+        token.governance = config.governance;
+        token.total_distributed = 0;
+        token.paused = false;
+        token.mint = ctx.accounts.mint.key();
+        token.vault = ctx.accounts.vault.key();
+        token.bump = ctx.bumps.token;
+        token.vault_bump = ctx.bumps.vault;
+        // Divide before multiplying so MAX_SUPPLY cannot overflow, the
+        // community pool takes the rounding remainder so allocations sum to supply
+        let share = |percent: u64| config.supply / 100 * percent;
+        let development = share(25);
+        let treasury = share(20);
+        let agent_incentives = share(15);
+        let team = share(10);
+        let community = config.supply - development - treasury - agent_incentives - team; // 30%
+        token.distribution_pools = DistributionPools {
+            community: Pool::new(community, config.distribution_start, None),
+            development: Pool::new(development, config.distribution_start, None), // 25%
+            treasury: Pool::new(treasury, config.distribution_start, None), // 20%
+            agent_incentives: Pool::new(agent_incentives, config.distribution_start, None), // 15%
+            team: Pool::new(
+                team, // 10%
+                config.distribution_start,
+                Some(config.distribution_start + 63072000), // 2 years
+            ),
+        };
+
+        // Mint the full supply into the vault, signed by the token PDA
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.token.to_account_info(),
+                },
+                &[&[TOKEN_SEED, &[ctx.accounts.token.bump]]],
+            ),
+            config.supply,
+        )?;
+
+        // Emit initialization event
+        emit!(TokenInitialized {
+            supply: config.supply,
+            authority: config.authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn distribute(ctx: Context<Distribute>, pool_type: PoolType, amount: u64) -> Result<()> {
+        let token = &mut ctx.accounts.token;
+        
+        // Check if contract is paused
+        require!(!token.paused, DistributionError::ContractPaused);
+
+        // Only keys holding the distributor role for this pool may spend it
+        require!(
+            ctx.accounts.pool_roles.is_distributor(&ctx.accounts.authority.key()),
+            DistributionError::UnauthorizedDistributor
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let pool = token.distribution_pools.get_pool_mut(pool_type);
+
+        // Validate distribution
+        require!(
+            amount <= pool.allocation - pool.distributed,
+            DistributionError::ExceedsAllocation
+        );
+        require!(
+            current_time >= pool.start_time,
+            DistributionError::DistributionNotStarted
+        );
+        if let Some(end_time) = pool.end_time {
+            require!(
+                current_time <= end_time,
+                DistributionError::DistributionEnded
+            );
+        }
+
+        // Rate limiting checks, tracked independently for each pool
+        pool.record_distribution(amount, current_time)?;
+
+        // Update distribution state
+        pool.distributed += amount;
+        token.total_distributed += amount;
+
+        // Transfer tokens
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.recipient.to_account_info(),
+                    authority: ctx.accounts.token.to_account_info(),
+                },
+                &[&[TOKEN_SEED, &[ctx.accounts.token.bump]]],
+            ),
+            amount,
+        )?;
+
+        // Emit distribution event
+        emit!(Distribution {
+            pool_type,
+            amount,
+            recipient: ctx.accounts.recipient.key(),
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_pool_roles(ctx: Context<InitializePoolRoles>, pool_type: PoolType) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.token.authority,
+            DistributionError::Unauthorized
+        );
+
+        let pool_roles = &mut ctx.accounts.pool_roles;
+        pool_roles.token = ctx.accounts.token.key();
+        pool_roles.pool_type = pool_type;
+        pool_roles.distributors = Vec::new();
+        pool_roles.bump = ctx.bumps.pool_roles;
+
+        Ok(())
+    }
+
+    pub fn add_distributor(ctx: Context<UpdatePoolRoles>, distributor: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.token.authority,
+            DistributionError::Unauthorized
+        );
+
+        let pool_roles = &mut ctx.accounts.pool_roles;
+        require!(
+            pool_roles.distributors.len() < MAX_POOL_DISTRIBUTORS,
+            DistributionError::MaxDistributorsReached
+        );
+        require!(
+            !pool_roles.is_distributor(&distributor),
+            DistributionError::DistributorAlreadyExists
+        );
+
+        pool_roles.distributors.push(distributor);
+
+        emit!(DistributorAdded {
+            pool_type: pool_roles.pool_type,
+            distributor,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn remove_distributor(ctx: Context<UpdatePoolRoles>, distributor: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.token.authority,
+            DistributionError::Unauthorized
+        );
+
+        let pool_roles = &mut ctx.accounts.pool_roles;
+        let position = pool_roles.distributors
+            .iter()
+            .position(|d| *d == distributor)
+            .ok_or(DistributionError::DistributorNotFound)?;
+
+        pool_roles.distributors.remove(position);
+
+        emit!(DistributorRemoved {
+            pool_type: pool_roles.pool_type,
+            distributor,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Only callable by the governance PDA, i.e. through an executed proposal
+    pub fn update_pool_limits(
+        ctx: Context<UpdatePoolLimits>,
+        pool_type: PoolType,
+        limits: PoolLimits,
+    ) -> Result<()> {
+        let token = &mut ctx.accounts.token;
+        require!(
+            limits.window_duration > 0 && limits.cooldown >= 0 && limits.max_per_window <= MAX_SUPPLY,
+            DistributionError::InvalidPoolLimits
+        );
+
+        let pool = token.distribution_pools.get_pool_mut(pool_type);
+        let old_limits = PoolLimits {
+            max_per_window: pool.max_per_window,
+            window_duration: pool.window_duration,
+            cooldown: pool.cooldown,
+        };
+        pool.max_per_window = limits.max_per_window;
+        pool.window_duration = limits.window_duration;
+        pool.cooldown = limits.cooldown;
+
+        emit!(PoolLimitsUpdated {
+            pool_type,
+            old_limits,
+            new_limits: limits,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_vesting_schedule(
+        ctx: Context<CreateVestingSchedule>,
+        pool_type: PoolType,
+        grant_id: u64,
+        config: VestingConfig,
+    ) -> Result<()> {
+        let token = &mut ctx.accounts.token;
+        require!(!token.paused, DistributionError::ContractPaused);
+        require!(
+            ctx.accounts.pool_roles.can_spend(&ctx.accounts.authority.key(), token),
+            DistributionError::UnauthorizedDistributor
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Validate schedule shape, back-dated schedules would unlock immediately
+        require!(
+            config.total_amount > 0
+                && config.start_time >= current_time
+                && config.start_time <= config.cliff_time
+                && config.cliff_time <= config.end_time
+                && config.start_time < config.end_time,
+            DistributionError::InvalidVestingSchedule
+        );
+        if let VestingKind::Stepped { period } = config.kind {
+            require!(
+                period > 0 && period <= config.end_time - config.start_time,
+                DistributionError::InvalidVestingSchedule
+            );
+        }
+
+        let pool = token.distribution_pools.get_pool_mut(pool_type);
+
+        // The full grant is reserved against the pool up front
+        require!(
+            config.total_amount <= pool.allocation - pool.distributed,
+            DistributionError::ExceedsAllocation
+        );
+        require!(
+            config.start_time >= pool.start_time,
+            DistributionError::DistributionNotStarted
+        );
+        if let Some(end_time) = pool.end_time {
+            require!(
+                current_time <= end_time,
+                DistributionError::DistributionEnded
+            );
+        }
+
+        pool.distributed += config.total_amount;
+        token.total_distributed += config.total_amount;
+
+        let schedule = &mut ctx.accounts.vesting_schedule;
+        schedule.token = token.key();
+        schedule.beneficiary = ctx.accounts.beneficiary.key();
+        schedule.pool_type = pool_type;
+        schedule.grant_id = grant_id;
+        schedule.total_amount = config.total_amount;
+        schedule.claimed_amount = 0;
+        schedule.start_time = config.start_time;
+        schedule.cliff_time = config.cliff_time;
+        schedule.end_time = config.end_time;
+        schedule.kind = config.kind;
+        schedule.bump = ctx.bumps.vesting_schedule;
+
+        emit!(VestingScheduleCreated {
+            beneficiary: schedule.beneficiary,
+            pool_type,
+            grant_id,
+            total_amount: config.total_amount,
+            cliff_time: config.cliff_time,
+            end_time: config.end_time,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless: anyone may crank a claim, tokens always go to the beneficiary
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        let token = &mut ctx.accounts.token;
+        require!(!token.paused, DistributionError::ContractPaused);
+
+        let schedule = &mut ctx.accounts.vesting_schedule;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let vested = schedule.vested_amount(current_time)?;
+        require!(vested > schedule.claimed_amount, DistributionError::NothingToClaim);
+
+        // Pays what the pool's window allows, the rest stays claimable
+        let pool = token.distribution_pools.get_pool_mut(schedule.pool_type);
+        let claimable = (vested - schedule.claimed_amount).min(pool.window_remaining(current_time));
+        require!(claimable > 0, DistributionError::RateLimitExceeded);
+        pool.record_payout(claimable, current_time)?;
+
+        schedule.claimed_amount += claimable;
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.beneficiary_tokens.to_account_info(),
+                    authority: ctx.accounts.token.to_account_info(),
+                },
+                &[&[TOKEN_SEED, &[ctx.accounts.token.bump]]],
+            ),
+            claimable,
+        )?;
+
+        emit!(VestedTokensClaimed {
+            beneficiary: schedule.beneficiary,
+            pool_type: schedule.pool_type,
+            grant_id: schedule.grant_id,
+            amount: claimable,
+            total_claimed: schedule.claimed_amount,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_merkle_distributor(
+        ctx: Context<CreateMerkleDistributor>,
+        campaign_id: u64,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        num_claims: u64,
+    ) -> Result<()> {
+        let token = &mut ctx.accounts.token;
+        require!(!token.paused, DistributionError::ContractPaused);
+        require!(
+            ctx.accounts.pool_roles.can_spend(&ctx.accounts.authority.key(), token),
+            DistributionError::UnauthorizedDistributor
+        );
+        require!(
+            total_amount > 0 && num_claims > 0,
+            DistributionError::InvalidAirdrop
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let pool = &mut token.distribution_pools.community;
+
+        // The whole campaign is reserved against the community pool up front
+        require!(
+            total_amount <= pool.allocation - pool.distributed,
+            DistributionError::ExceedsAllocation
+        );
+        require!(
+            current_time >= pool.start_time,
+            DistributionError::DistributionNotStarted
+        );
+        if let Some(end_time) = pool.end_time {
+            require!(
+                current_time <= end_time,
+                DistributionError::DistributionEnded
+            );
+        }
+
+        pool.distributed += total_amount;
+        token.total_distributed += total_amount;
+
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.token = token.key();
+        distributor.campaign_id = campaign_id;
+        distributor.merkle_root = merkle_root;
+        distributor.total_amount = total_amount;
+        distributor.claimed_amount = 0;
+        distributor.num_claims = num_claims;
+        distributor.num_claimed = 0;
+        distributor.created_at = current_time;
+        distributor.bump = ctx.bumps.distributor;
+
+        emit!(MerkleDistributorCreated {
+            distributor: distributor.key(),
+            campaign_id,
+            merkle_root,
+            total_amount,
+            num_claims,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless: any payer may create the bitmap pages a campaign needs
+    pub fn init_claim_bitmap(ctx: Context<InitClaimBitmap>, page_index: u64) -> Result<()> {
+        let distributor = &ctx.accounts.distributor;
+        require!(
+            page_index < distributor.num_bitmap_pages(),
+            DistributionError::InvalidClaimIndex
+        );
+
+        let bitmap = &mut ctx.accounts.claim_bitmap;
+        bitmap.distributor = distributor.key();
+        bitmap.page_index = page_index;
+
+        Ok(())
+    }
+
+    pub fn claim_airdrop(
+        ctx: Context<ClaimAirdrop>,
+        index: u64,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let token = &mut ctx.accounts.token;
+        require!(!token.paused, DistributionError::ContractPaused);
+
+        let distributor = &mut ctx.accounts.distributor;
+        let claimant = ctx.accounts.claimant.key();
+        require!(
+            index < distributor.num_claims,
+            DistributionError::InvalidClaimIndex
+        );
+
+        let leaf = keccak::hashv(&[
+            &[0u8],
+            &index.to_le_bytes(),
+            claimant.as_ref(),
+            &amount.to_le_bytes(),
+        ]);
+        require!(
+            verify_merkle_proof(&proof, distributor.merkle_root, leaf.0),
+            DistributionError::InvalidProof
+        );
+
+        let bitmap = &mut ctx.accounts.claim_bitmap;
+        require!(
+            !bitmap.is_claimed(index),
+            DistributionError::AlreadyClaimed
+        );
+        require!(
+            amount <= distributor.total_amount - distributor.claimed_amount,
+            DistributionError::ExceedsAllocation
+        );
+
+        // Leaves are paid whole, so a claim over the window waits for the next one
+        let current_time = Clock::get()?.unix_timestamp;
+        token.distribution_pools.community.record_payout(amount, current_time)?;
+
+        bitmap.set_claimed(index);
+        distributor.claimed_amount += amount;
+        distributor.num_claimed += 1;
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.claimant_tokens.to_account_info(),
+                    authority: ctx.accounts.token.to_account_info(),
+                },
+                &[&[TOKEN_SEED, &[ctx.accounts.token.bump]]],
+            ),
+            amount,
+        )?;
+
+        emit!(AirdropClaimed {
+            distributor: distributor.key(),
+            index,
+            claimant,
+            amount,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_stake_pool(
+        ctx: Context<InitializeStakePool>,
+        reward_pool_type: PoolType,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.token.authority,
+            DistributionError::Unauthorized
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        stake_pool.token = ctx.accounts.token.key();
+        stake_pool.reward_pool_type = reward_pool_type;
+        stake_pool.total_staked = 0;
+        stake_pool.total_weighted = 0;
+        stake_pool.reward_rate = 0;
+        stake_pool.reward_end_time = current_time;
+        stake_pool.last_update_time = current_time;
+        stake_pool.reward_per_token_stored = 0;
+        stake_pool.bump = ctx.bumps.stake_pool;
+        stake_pool.vault_bump = ctx.bumps.stake_vault;
+
+        emit!(StakePoolInitialized {
+            stake_pool: stake_pool.key(),
+            reward_pool_type,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    // Reserves `amount` from the stake pool's reward PoolType and streams it
+    // to stakers over `duration` seconds, rolling over any unstreamed rewards
+    pub fn fund_stake_rewards(
+        ctx: Context<FundStakeRewards>,
+        amount: u64,
+        duration: i64,
+    ) -> Result<()> {
+        let token = &mut ctx.accounts.token;
+        require!(!token.paused, DistributionError::ContractPaused);
+        require!(
+            ctx.accounts.pool_roles.can_spend(&ctx.accounts.authority.key(), token),
+            DistributionError::UnauthorizedDistributor
+        );
+        require!(
+            amount > 0 && duration > 0,
+            DistributionError::InvalidRewardSchedule
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let pool = token.distribution_pools.get_pool_mut(stake_pool.reward_pool_type);
+
+        require!(
+            amount <= pool.allocation - pool.distributed,
+            DistributionError::ExceedsAllocation
+        );
+        require!(
+            current_time >= pool.start_time,
+            DistributionError::DistributionNotStarted
+        );
+        if let Some(end_time) = pool.end_time {
+            require!(
+                current_time <= end_time,
+                DistributionError::DistributionEnded
+            );
+        }
+
+        pool.distributed += amount;
+        token.total_distributed += amount;
+
+        stake_pool.update_rewards(current_time)?;
+
+        let leftover = if current_time < stake_pool.reward_end_time {
+            (stake_pool.reward_end_time - current_time) as u64 * stake_pool.reward_rate
+        } else {
+            0
+        };
+        stake_pool.reward_rate = (amount + leftover) / duration as u64;
+        stake_pool.reward_end_time = current_time + duration;
+        stake_pool.last_update_time = current_time;
+        require!(
+            stake_pool.reward_rate > 0,
+            DistributionError::InvalidRewardSchedule
+        );
+
+        emit!(StakeRewardsFunded {
+            stake_pool: stake_pool.key(),
+            reward_pool_type: stake_pool.reward_pool_type,
+            amount,
+            reward_rate: stake_pool.reward_rate,
+            reward_end_time: stake_pool.reward_end_time,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn open_stake_position(ctx: Context<OpenStakePosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.stake_pool = ctx.accounts.stake_pool.key();
+        position.owner = ctx.accounts.owner.key();
+        position.amount = 0;
+        position.weighted_amount = 0;
+        position.lock_end = 0;
+        position.multiplier_bps = BPS_DENOMINATOR;
+        position.reward_per_token_paid = ctx.accounts.stake_pool.reward_per_token_stored;
+        position.last_settled = Clock::get()?.unix_timestamp;
+        position.rewards_owed = 0;
+        position.bump = ctx.bumps.position;
+
+        Ok(())
+    }
+
+    pub fn stake(ctx: Context<UpdateStake>, amount: u64, lock_duration: i64) -> Result<()> {
+        require!(!ctx.accounts.token.paused, DistributionError::ContractPaused);
+        require!(amount > 0, DistributionError::InvalidStakeAmount);
+        require!(
+            (0..=MAX_STAKE_LOCK_DURATION).contains(&lock_duration),
+            DistributionError::InvalidLockDuration
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let position = &mut ctx.accounts.position;
+
+        stake_pool.update_rewards(current_time)?;
+        position.settle(stake_pool.reward_per_token_stored, current_time)?;
+
+        // A new stake can only extend an existing lock, never shorten it
+        position.lock_end = position.lock_end.max(current_time + lock_duration);
+        position.multiplier_bps = lock_multiplier_bps(position.lock_end - current_time);
+        position.amount += amount;
+        stake_pool.total_staked += amount;
+        stake_pool.reweight(position);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.owner_tokens.to_account_info(),
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        emit!(Staked {
+            owner: position.owner,
+            amount,
+            lock_end: position.lock_end,
+            multiplier_bps: position.multiplier_bps,
+            total_staked: stake_pool.total_staked,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn unstake(ctx: Context<UpdateStake>, amount: u64) -> Result<()> {
+        require!(!ctx.accounts.token.paused, DistributionError::ContractPaused);
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let position = &mut ctx.accounts.position;
+
+        require!(
+            amount > 0 && amount <= position.amount,
+            DistributionError::InvalidStakeAmount
+        );
+        require!(
+            current_time >= position.lock_end,
+            DistributionError::StakeLocked
+        );
+
+        stake_pool.update_rewards(current_time)?;
+        position.settle(stake_pool.reward_per_token_stored, current_time)?;
+
+        // The lock has expired, so the remaining stake earns at the base rate
+        stake_pool.expire_lock(position, current_time);
+        position.amount -= amount;
+        stake_pool.total_staked -= amount;
+        stake_pool.reweight(position);
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.stake_vault.to_account_info(),
+                    to: ctx.accounts.owner_tokens.to_account_info(),
+                    authority: ctx.accounts.token.to_account_info(),
+                },
+                &[&[TOKEN_SEED, &[ctx.accounts.token.bump]]],
+            ),
+            amount,
+        )?;
+
+        emit!(Unstaked {
+            owner: position.owner,
+            amount,
+            total_staked: stake_pool.total_staked,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless crank, drops an expired lock back to the base reward weight
+    pub fn expire_stake_lock(ctx: Context<ExpireStakeLock>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let position = &mut ctx.accounts.position;
+
+        require!(
+            current_time >= position.lock_end,
+            DistributionError::StakeLocked
+        );
+
+        stake_pool.update_rewards(current_time)?;
+        position.settle(stake_pool.reward_per_token_stored, current_time)?;
+        stake_pool.expire_lock(position, current_time);
+
+        emit!(StakeLockExpired {
+            owner: position.owner,
+            weighted_amount: position.weighted_amount,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn claim_stake_rewards(ctx: Context<ClaimStakeRewards>) -> Result<()> {
+        require!(!ctx.accounts.token.paused, DistributionError::ContractPaused);
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let position = &mut ctx.accounts.position;
+
+        stake_pool.update_rewards(current_time)?;
+        position.settle(stake_pool.reward_per_token_stored, current_time)?;
+        stake_pool.expire_lock(position, current_time);
+
+        require!(position.rewards_owed > 0, DistributionError::NothingToClaim);
+
+        // Pays what the reward pool's window allows, the rest stays owed
+        let pool = ctx.accounts.token.distribution_pools.get_pool_mut(stake_pool.reward_pool_type);
+        let amount = position.rewards_owed.min(pool.window_remaining(current_time));
+        require!(amount > 0, DistributionError::RateLimitExceeded);
+        pool.record_payout(amount, current_time)?;
+        position.rewards_owed -= amount;
+
+        // Rewards were reserved from the pool allocation and still sit in the vault
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.owner_tokens.to_account_info(),
+                    authority: ctx.accounts.token.to_account_info(),
+                },
+                &[&[TOKEN_SEED, &[ctx.accounts.token.bump]]],
+            ),
+            amount,
+        )?;
+
+        emit!(StakeRewardsClaimed {
+            owner: position.owner,
+            amount,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+        let token = &ctx.accounts.token;
+        require!(!token.paused, DistributionError::ContractPaused);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.from.to_account_info(),
+                    to: ctx.accounts.to.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        Ok(())
+    }
+
+    pub fn delegate(ctx: Context<Delegate>, amount: u64) -> Result<()> {
+        let token = &ctx.accounts.token;
+        require!(!token.paused, DistributionError::ContractPaused);
+
+        token::approve(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::Approve {
+                    to: ctx.accounts.delegate_account.to_account_info(),
+                    delegate: ctx.accounts.delegate.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        Ok(())
+    }
+
+    pub fn pause(ctx: Context<EmergencyAction>) -> Result<()> {
+        let token = &mut ctx.accounts.token;
+        require!(
+            ctx.accounts.admin.key() == token.emergency_admin,
+            DistributionError::Unauthorized
+        );
+        
+        token.paused = true;
+
+        emit!(EmergencyAction {
+            action_type: EmergencyActionType::Pause,
+            initiator: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn unpause(ctx: Context<EmergencyAction>) -> Result<()> {
+        let token = &mut ctx.accounts.token;
+        require!(
+            ctx.accounts.admin.key() == token.emergency_admin,
+            DistributionError::Unauthorized
+        );
+        
+        token.paused = false;
+
+        emit!(EmergencyAction {
+            action_type: EmergencyActionType::Unpause,
+            initiator: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_emergency_admin(ctx: Context<EmergencyAction>, new_admin: Pubkey) -> Result<()> {
+        let token = &mut ctx.accounts.token;
+        require!(
+            ctx.accounts.admin.key() == token.emergency_admin,
+            DistributionError::Unauthorized
+        );
+        
+        token.emergency_admin = new_admin;
+
+        emit!(EmergencyAction {
+            action_type: EmergencyActionType::UpdateAdmin,
+            initiator: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+fn lock_multiplier_bps(lock_duration: i64) -> u64 {
+    let lock_duration = lock_duration.clamp(0, MAX_STAKE_LOCK_DURATION) as u64;
+    BPS_DENOMINATOR
+        + (MAX_LOCK_MULTIPLIER_BPS - BPS_DENOMINATOR) * lock_duration / MAX_STAKE_LOCK_DURATION as u64
+}
+
+// Leaves are hashed as keccak(0x00 || index || claimant || amount) and inner
+// nodes as keccak(0x01 || min(a, b) || max(a, b)), so proofs carry no ordering
+// bits and a leaf can never be passed off as an inner node.
+fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed = leaf;
+    for node in proof {
+        computed = if computed <= *node {
+            keccak::hashv(&[&[1u8], &computed, node]).0
+        } else {
+            keccak::hashv(&[&[1u8], node, &computed]).0
+        };
+    }
+    computed == root
+}
+
+#[derive(Accounts)]
+#[instruction(config: TokenConfig)]
+pub struct Initialize<'info> {
+    #[account(init, payer = authority, space = Token::LEN, seeds = [TOKEN_SEED], bump)]
+    pub token: Account<'info, Token>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [MINT_SEED, token.key().as_ref()],
+        bump,
+        mint::decimals = config.decimals,
+        mint::authority = token
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [VAULT_SEED, token.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = token
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    // Only the program's upgrade authority may claim the singleton token PDA
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::SadlToken>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ DistributionError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub token_program: Program<'info, SplToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_type: PoolType)]
+pub struct Distribute<'info> {
+    #[account(mut, seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(
+        has_one = token,
+        seeds = [POOL_ROLES_SEED, token.key().as_ref(), &[pool_type as u8]],
+        bump = pool_roles.bump
+    )]
+    pub pool_roles: Account<'info, PoolRoles>,
+    #[account(mut, seeds = [VAULT_SEED, token.key().as_ref()], bump = token.vault_bump)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, constraint = recipient.mint == token.mint @ DistributionError::InvalidMint)]
+    pub recipient: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, SplToken>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_type: PoolType)]
+pub struct InitializePoolRoles<'info> {
+    #[account(seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(
+        init,
+        payer = authority,
+        space = PoolRoles::LEN,
+        seeds = [POOL_ROLES_SEED, token.key().as_ref(), &[pool_type as u8]],
+        bump
+    )]
+    pub pool_roles: Account<'info, PoolRoles>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePoolRoles<'info> {
+    #[account(seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(
+        mut,
+        has_one = token,
+        seeds = [POOL_ROLES_SEED, token.key().as_ref(), &[pool_roles.pool_type as u8]],
+        bump = pool_roles.bump
+    )]
+    pub pool_roles: Account<'info, PoolRoles>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePoolLimits<'info> {
+    #[account(mut, seeds = [TOKEN_SEED], bump = token.bump, has_one = governance)]
+    pub token: Account<'info, Token>,
+    pub governance: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_type: PoolType, grant_id: u64)]
+pub struct CreateVestingSchedule<'info> {
+    #[account(mut, seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(
+        has_one = token,
+        seeds = [POOL_ROLES_SEED, token.key().as_ref(), &[pool_type as u8]],
+        bump = pool_roles.bump
+    )]
+    pub pool_roles: Account<'info, PoolRoles>,
+    #[account(
+        init,
+        payer = authority,
+        space = VestingSchedule::LEN,
+        seeds = [
+            VESTING_SEED,
+            token.key().as_ref(),
+            beneficiary.key().as_ref(),
+            &[pool_type as u8],
+            &grant_id.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+    pub beneficiary: SystemAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(mut, seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(
+        mut,
+        has_one = token,
+        seeds = [
+            VESTING_SEED,
+            token.key().as_ref(),
+            vesting_schedule.beneficiary.as_ref(),
+            &[vesting_schedule.pool_type as u8],
+            &vesting_schedule.grant_id.to_le_bytes(),
+        ],
+        bump = vesting_schedule.bump
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+    #[account(mut, seeds = [VAULT_SEED, token.key().as_ref()], bump = token.vault_bump)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = beneficiary_tokens.owner == vesting_schedule.beneficiary
+            @ DistributionError::InvalidBeneficiary,
+        constraint = beneficiary_tokens.mint == token.mint @ DistributionError::InvalidMint
+    )]
+    pub beneficiary_tokens: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, SplToken>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: u64)]
+pub struct CreateMerkleDistributor<'info> {
+    #[account(mut, seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(
+        has_one = token,
+        seeds = [POOL_ROLES_SEED, token.key().as_ref(), &[PoolType::Community as u8]],
+        bump = pool_roles.bump
+    )]
+    pub pool_roles: Account<'info, PoolRoles>,
+    #[account(
+        init,
+        payer = authority,
+        space = MerkleDistributor::LEN,
+        seeds = [MERKLE_DISTRIBUTOR_SEED, token.key().as_ref(), &campaign_id.to_le_bytes()],
+        bump
+    )]
+    pub distributor: Account<'info, MerkleDistributor>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(page_index: u64)]
+pub struct InitClaimBitmap<'info> {
+    pub distributor: Account<'info, MerkleDistributor>,
+    #[account(
+        init,
+        payer = payer,
+        space = ClaimBitmap::LEN,
+        seeds = [CLAIM_BITMAP_SEED, distributor.key().as_ref(), &page_index.to_le_bytes()],
+        bump
+    )]
+    pub claim_bitmap: Account<'info, ClaimBitmap>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct ClaimAirdrop<'info> {
+    #[account(mut, seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(
+        mut,
+        has_one = token,
+        seeds = [
+            MERKLE_DISTRIBUTOR_SEED,
+            token.key().as_ref(),
+            &distributor.campaign_id.to_le_bytes(),
+        ],
+        bump = distributor.bump
+    )]
+    pub distributor: Account<'info, MerkleDistributor>,
+    #[account(
+        mut,
+        seeds = [
+            CLAIM_BITMAP_SEED,
+            distributor.key().as_ref(),
+            &(index / CLAIMS_PER_BITMAP_PAGE).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub claim_bitmap: Account<'info, ClaimBitmap>,
+    #[account(mut, seeds = [VAULT_SEED, token.key().as_ref()], bump = token.vault_bump)]
+    pub vault: Account<'info, TokenAccount>,
+    pub claimant: Signer<'info>,
+    #[account(
+        mut,
+        constraint = claimant_tokens.owner == claimant.key() @ DistributionError::InvalidBeneficiary,
+        constraint = claimant_tokens.mint == token.mint @ DistributionError::InvalidMint
+    )]
+    pub claimant_tokens: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, SplToken>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeStakePool<'info> {
+    #[account(seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(
+        init,
+        payer = authority,
+        space = StakePool::LEN,
+        seeds = [STAKE_POOL_SEED, token.key().as_ref()],
+        bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(address = token.mint @ DistributionError::InvalidMint)]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [STAKE_VAULT_SEED, token.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = token
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, SplToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundStakeRewards<'info> {
+    #[account(mut, seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(
+        mut,
+        has_one = token,
+        seeds = [STAKE_POOL_SEED, token.key().as_ref()],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        has_one = token,
+        seeds = [POOL_ROLES_SEED, token.key().as_ref(), &[stake_pool.reward_pool_type as u8]],
+        bump = pool_roles.bump
+    )]
+    pub pool_roles: Account<'info, PoolRoles>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenStakePosition<'info> {
+    #[account(seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(
+        has_one = token,
+        seeds = [STAKE_POOL_SEED, token.key().as_ref()],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        init,
+        payer = owner,
+        space = StakePosition::LEN,
+        seeds = [STAKE_POSITION_SEED, stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, StakePosition>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateStake<'info> {
+    #[account(seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(
+        mut,
+        has_one = token,
+        seeds = [STAKE_POOL_SEED, token.key().as_ref()],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        has_one = stake_pool,
+        has_one = owner,
+        seeds = [STAKE_POSITION_SEED, stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, StakePosition>,
+    #[account(mut, seeds = [STAKE_VAULT_SEED, token.key().as_ref()], bump = stake_pool.vault_bump)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = owner_tokens.owner == owner.key() @ DistributionError::InvalidBeneficiary,
+        constraint = owner_tokens.mint == token.mint @ DistributionError::InvalidMint
+    )]
+    pub owner_tokens: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, SplToken>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireStakeLock<'info> {
+    #[account(seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(
+        mut,
+        has_one = token,
+        seeds = [STAKE_POOL_SEED, token.key().as_ref()],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        has_one = stake_pool,
+        seeds = [STAKE_POSITION_SEED, stake_pool.key().as_ref(), position.owner.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, StakePosition>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStakeRewards<'info> {
+    #[account(mut, seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(
+        mut,
+        has_one = token,
+        seeds = [STAKE_POOL_SEED, token.key().as_ref()],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    #[account(
+        mut,
+        has_one = stake_pool,
+        has_one = owner,
+        seeds = [STAKE_POSITION_SEED, stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, StakePosition>,
+    #[account(mut, seeds = [VAULT_SEED, token.key().as_ref()], bump = token.vault_bump)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = owner_tokens.owner == owner.key() @ DistributionError::InvalidBeneficiary,
+        constraint = owner_tokens.mint == token.mint @ DistributionError::InvalidMint
+    )]
+    pub owner_tokens: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, SplToken>,
+}
+
+#[derive(Accounts)]
+pub struct Transfer<'info> {
+    #[account(mut, seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(mut)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, SplToken>,
+}
+
+#[derive(Accounts)]
+pub struct Delegate<'info> {
+    #[account(mut, seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    #[account(mut)]
+    pub delegate_account: Account<'info, TokenAccount>,
+    pub delegate: AccountInfo<'info>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, SplToken>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyAction<'info> {
+    #[account(mut, seeds = [TOKEN_SEED], bump = token.bump)]
+    pub token: Account<'info, Token>,
+    pub admin: Signer<'info>,
+}
+
+#[account]
+pub struct Token {
+    pub supply: u64,
+    pub decimals: u8,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub total_distributed: u64,
+    pub distribution_pools: DistributionPools,
+    pub paused: bool,
+    pub emergency_admin: Pubkey,
+    pub governance: Pubkey, // governance PDA allowed to retune pool limits
+}
+
+impl Token {
+    pub const LEN: usize = 8 + // discriminator
+        8 + // supply
+        1 + // decimals
+        32 + // authority
+        32 + // mint
+        32 + // vault
+        1 + // bump
+        1 + // vault_bump
+        8 + // total_distributed
+        DistributionPools::LEN +
+        1 + // paused
+        32 + // emergency_admin
+        32; // governance
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DistributionPools {
+    pub community: Pool,
+    pub development: Pool,
+    pub treasury: Pool,
+    pub agent_incentives: Pool,
+    pub team: Pool,
+}
+
+impl DistributionPools {
+    pub const LEN: usize = Pool::LEN * 5;
+
+    pub fn get_pool_mut(&mut self, pool_type: PoolType) -> &mut Pool {
+        match pool_type {
+            PoolType::Community => &mut self.community,
+            PoolType::Development => &mut self.development,
+            PoolType::Treasury => &mut self.treasury,
+            PoolType::AgentIncentives => &mut self.agent_incentives,
+            PoolType::Team => &mut self.team,
+        }
+    }
+}
+
+// `distributed` counts reservations, grants, campaigns and reward streams
+// as well as direct payouts, so the allocation is never oversubscribed.
+// The rolling window limits tokens actually leaving the vault: `distribute`
+// and the permissionless claims against vesting schedules, airdrops and
+// stake rewards. The cooldown only spaces out discretionary `distribute`
+// calls, so claims from many beneficiaries are never blocked by each other.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Pool {
+    pub allocation: u64,
+    pub distributed: u64,
+    pub start_time: i64,
+    pub end_time: Option<i64>,
+    pub max_per_window: u64,
+    pub window_duration: i64,
+    pub cooldown: i64,
+    pub window_start: i64,
+    pub window_distributed: u64,
+    pub last_distribution: i64,
+}
+
+impl Pool {
+    pub const LEN: usize = 8 + 8 + 8 + 9 + // allocation, distributed, start_time, end_time
+        8 + 8 + 8 + // max_per_window, window_duration, cooldown
+        8 + 8 + 8; // window_start, window_distributed, last_distribution
+
+    pub fn new(allocation: u64, start_time: i64, end_time: Option<i64>) -> Self {
+        Self {
+            allocation,
+            distributed: 0,
+            start_time,
+            end_time,
+            max_per_window: MAX_DISTRIBUTION_RATE,
+            window_duration: DISTRIBUTION_WINDOW,
+            cooldown: DISTRIBUTION_COOLDOWN,
+            window_start: 0,
+            window_distributed: 0,
+            last_distribution: 0,
+        }
+    }
+
+    pub fn record_distribution(&mut self, amount: u64, current_time: i64) -> Result<()> {
+        require!(
+            current_time >= self.last_distribution + self.cooldown,
+            DistributionError::CooldownNotElapsed
+        );
+
+        self.record_payout(amount, current_time)?;
+        self.last_distribution = current_time;
+        Ok(())
+    }
+
+    // Counts a claim against the rolling window without the cooldown
+    pub fn record_payout(&mut self, amount: u64, current_time: i64) -> Result<()> {
+        require!(
+            amount <= self.window_remaining(current_time),
+            DistributionError::RateLimitExceeded
+        );
+
+        self.window_distributed += amount;
+        Ok(())
+    }
+
+    // Starts a fresh window once the current one has elapsed
+    pub fn window_remaining(&mut self, current_time: i64) -> u64 {
+        if current_time >= self.window_start + self.window_duration {
+            self.window_start = current_time;
+            self.window_distributed = 0;
+        }
+        self.max_per_window.saturating_sub(self.window_distributed)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PoolLimits {
+    pub max_per_window: u64,
+    pub window_duration: i64,
+    pub cooldown: i64,
+}
+
+#[account]
+pub struct PoolRoles {
+    pub token: Pubkey,
+    pub pool_type: PoolType,
+    pub distributors: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl PoolRoles {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // token
+        1 + // pool_type
+        4 + (32 * MAX_POOL_DISTRIBUTORS) + // distributors
+        1; // bump
+
+    pub fn is_distributor(&self, key: &Pubkey) -> bool {
+        self.distributors.contains(key)
+    }
+
+    // Reservations out of the pool (vesting, airdrops, staking rewards) are
+    // also open to the governance PDA
+    pub fn can_spend(&self, key: &Pubkey, token: &Token) -> bool {
+        self.is_distributor(key) || *key == token.governance
+    }
+}
+
+#[account]
+pub struct VestingSchedule {
+    pub token: Pubkey,
+    pub beneficiary: Pubkey,
+    pub pool_type: PoolType,
+    pub grant_id: u64, // lets a beneficiary hold several grants from one pool
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub end_time: i64,
+    pub kind: VestingKind,
+    pub bump: u8,
+}
+
+impl VestingSchedule {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // token
+        32 + // beneficiary
+        1 + // pool_type
+        8 + // grant_id
+        8 + // total_amount
+        8 + // claimed_amount
+        8 + // start_time
+        8 + // cliff_time
+        8 + // end_time
+        VestingKind::LEN +
+        1; // bump
+
+    pub fn vested_amount(&self, current_time: i64) -> Result<u64> {
+        if current_time < self.cliff_time {
+            return Ok(0);
+        }
+        if current_time >= self.end_time {
+            return Ok(self.total_amount);
+        }
+
+        let elapsed = match self.kind {
+            VestingKind::Linear => current_time - self.start_time,
+            VestingKind::Stepped { period } => {
+                (current_time - self.start_time) / period * period
+            }
+        };
+        let duration = self.end_time - self.start_time;
+
+        let vested = (self.total_amount as u128)
+            .checked_mul(elapsed as u128)
+            .ok_or(DistributionError::MathOverflow)?
+            / duration as u128;
+        Ok(vested as u64)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum VestingKind {
+    Linear,
+    Stepped { period: i64 }, // unlocks in equal tranches every `period` seconds
+}
+
+impl VestingKind {
+    pub const LEN: usize = 1 + 8;
+}
+
+#[account]
+pub struct MerkleDistributor {
+    pub token: Pubkey,
+    pub campaign_id: u64,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub num_claims: u64,
+    pub num_claimed: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl MerkleDistributor {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // token
+        8 + // campaign_id
+        32 + // merkle_root
+        8 + // total_amount
+        8 + // claimed_amount
+        8 + // num_claims
+        8 + // num_claimed
+        8 + // created_at
+        1; // bump
+
+    pub fn num_bitmap_pages(&self) -> u64 {
+        self.num_claims.div_ceil(CLAIMS_PER_BITMAP_PAGE)
+    }
+}
+
+#[account]
+pub struct ClaimBitmap {
+    pub distributor: Pubkey,
+    pub page_index: u64,
+    pub bits: [u8; CLAIM_BITMAP_BYTES],
+}
+
+impl ClaimBitmap {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // distributor
+        8 + // page_index
+        CLAIM_BITMAP_BYTES; // bits
+
+    fn bit_position(index: u64) -> (usize, u8) {
+        let offset = index % CLAIMS_PER_BITMAP_PAGE;
+        ((offset / 8) as usize, 1 << (offset % 8))
+    }
+
+    pub fn is_claimed(&self, index: u64) -> bool {
+        let (byte, mask) = Self::bit_position(index);
+        self.bits[byte] & mask != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u64) {
+        let (byte, mask) = Self::bit_position(index);
+        self.bits[byte] |= mask;
+    }
+}
+
+#[account]
+pub struct StakePool {
+    pub token: Pubkey,
+    pub reward_pool_type: PoolType,
+    pub total_staked: u64,
+    pub total_weighted: u64,
+    pub reward_rate: u64, // tokens streamed per second
+    pub reward_end_time: i64,
+    pub last_update_time: i64,
+    pub reward_per_token_stored: u128, // scaled by REWARD_PRECISION
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl StakePool {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // token
+        1 + // reward_pool_type
+        8 + // total_staked
+        8 + // total_weighted
+        8 + // reward_rate
+        8 + // reward_end_time
+        8 + // last_update_time
+        16 + // reward_per_token_stored
+        1 + // bump
+        1; // vault_bump
+
+    pub fn reward_per_token(&self, current_time: i64) -> Result<u128> {
+        let applicable_time = current_time.min(self.reward_end_time);
+        if self.total_weighted == 0 || applicable_time <= self.last_update_time {
+            return Ok(self.reward_per_token_stored);
+        }
+
+        let elapsed = (applicable_time - self.last_update_time) as u128;
+        let accrued = elapsed
+            .checked_mul(self.reward_rate as u128)
+            .and_then(|r| r.checked_mul(REWARD_PRECISION))
+            .ok_or(DistributionError::MathOverflow)?
+            / self.total_weighted as u128;
+
+        self.reward_per_token_stored
+            .checked_add(accrued)
+            .ok_or_else(|| DistributionError::MathOverflow.into())
+    }
+
+    pub fn update_rewards(&mut self, current_time: i64) -> Result<()> {
+        // With nothing staked no rewards are emitted, so restart whatever was
+        // left of the stream from now instead of stranding it in the vault
+        if self.total_weighted == 0 {
+            if current_time > self.last_update_time && self.last_update_time < self.reward_end_time {
+                self.reward_end_time = current_time + (self.reward_end_time - self.last_update_time);
+            }
+            self.last_update_time = current_time.max(self.last_update_time);
+            return Ok(());
+        }
+
+        self.reward_per_token_stored = self.reward_per_token(current_time)?;
+        self.last_update_time = current_time.min(self.reward_end_time).max(self.last_update_time);
+        Ok(())
+    }
+
+    // Drops a position whose lock has ended back to the base weight
+    pub fn expire_lock(&mut self, position: &mut StakePosition, current_time: i64) {
+        if current_time >= position.lock_end && position.multiplier_bps != BPS_DENOMINATOR {
+            position.multiplier_bps = BPS_DENOMINATOR;
+            self.reweight(position);
+        }
+    }
+
+    // Recomputes a position's weighted stake and applies the delta to the pool
+    pub fn reweight(&mut self, position: &mut StakePosition) {
+        let weighted = (position.amount as u128 * position.multiplier_bps as u128
+            / BPS_DENOMINATOR as u128) as u64;
+        self.total_weighted = self.total_weighted - position.weighted_amount + weighted;
+        position.weighted_amount = weighted;
+    }
+}
+
+#[account]
+pub struct StakePosition {
+    pub stake_pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub weighted_amount: u64,
+    pub lock_end: i64,
+    pub multiplier_bps: u64,
+    pub reward_per_token_paid: u128,
+    pub last_settled: i64,
+    pub rewards_owed: u64,
+    pub bump: u8,
+}
+
+impl StakePosition {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // stake_pool
+        32 + // owner
+        8 + // amount
+        8 + // weighted_amount
+        8 + // lock_end
+        8 + // multiplier_bps
+        16 + // reward_per_token_paid
+        8 + // last_settled
+        8 + // rewards_owed
+        1; // bump
+
+    // The lock boost only earns up to lock_end: when a lock ended since the
+    // last settlement, the boost is prorated by the time it was still active,
+    // so leaving an expired lock uncranked gains its owner nothing
+    pub fn settle(&mut self, reward_per_token: u128, current_time: i64) -> Result<()> {
+        let mut weighted = self.weighted_amount as u128;
+        if current_time > self.lock_end && current_time > self.last_settled {
+            let boost = weighted.saturating_sub(self.amount as u128);
+            let boosted_time = (self.lock_end - self.last_settled).max(0) as u128;
+            let elapsed = (current_time - self.last_settled) as u128;
+            weighted = self.amount as u128 + boost * boosted_time / elapsed;
+        }
+
+        let earned = weighted
+            .checked_mul(reward_per_token - self.reward_per_token_paid)
+            .ok_or(DistributionError::MathOverflow)?
+            / REWARD_PRECISION;
+
+        self.rewards_owed = self.rewards_owed
+            .checked_add(earned as u64)
+            .ok_or(DistributionError::MathOverflow)?;
+        self.reward_per_token_paid = reward_per_token;
+        self.last_settled = current_time;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct VestingConfig {
+    pub total_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub end_time: i64,
+    pub kind: VestingKind,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum PoolType {
+    Community,
+    Development,
+    Treasury,
+    AgentIncentives,
+    Team,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct TokenConfig {
+    pub supply: u64,
+    pub decimals: u8,
+    pub authority: Pubkey,
+    pub governance: Pubkey,
+    pub distribution_start: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub enum EmergencyActionType {
+    Pause,
+    Unpause,
+    UpdateAdmin,
+}
+
+#[error_code]
+pub enum DistributionError {
+    #[msg("Distribution amount exceeds pool allocation")]
+    ExceedsAllocation,
+    #[msg("Distribution period has not started")]
+    DistributionNotStarted,
+    #[msg("Distribution period has ended")]
+    DistributionEnded,
+    #[msg("Contract is paused")]
+    ContractPaused,
+    #[msg("Distribution rate limit exceeded")]
+    RateLimitExceeded,
+    #[msg("Distribution cooldown period not elapsed")]
+    CooldownNotElapsed,
+    #[msg("Supply cap exceeded")]
+    SupplyCapExceeded,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid vesting schedule")]
+    InvalidVestingSchedule,
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+    #[msg("Token account is not owned by the beneficiary")]
+    InvalidBeneficiary,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Invalid airdrop campaign")]
+    InvalidAirdrop,
+    #[msg("Claim index out of range")]
+    InvalidClaimIndex,
+    #[msg("Invalid merkle proof")]
+    InvalidProof,
+    #[msg("Airdrop already claimed")]
+    AlreadyClaimed,
+    #[msg("Invalid pool limits")]
+    InvalidPoolLimits,
+    #[msg("Signer is not a distributor for this pool")]
+    UnauthorizedDistributor,
+    #[msg("Maximum number of distributors reached")]
+    MaxDistributorsReached,
+    #[msg("Distributor already exists")]
+    DistributorAlreadyExists,
+    #[msg("Distributor not found")]
+    DistributorNotFound,
+    #[msg("Token account mint does not match SADL mint")]
+    InvalidMint,
+    #[msg("Invalid reward schedule")]
+    InvalidRewardSchedule,
+    #[msg("Invalid stake amount")]
+    InvalidStakeAmount,
+    #[msg("Invalid lock duration")]
+    InvalidLockDuration,
+    #[msg("Stake is still locked")]
+    StakeLocked,
+}
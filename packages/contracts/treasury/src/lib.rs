@@ -1,433 +1,1412 @@
-use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-
-declare_id!("TreasHZQVHDR2qXkP3FvPQ4FvXJKo6E9gA");
-
-// Constants for rate limiting
-pub const MAX_DAILY_TRANSACTION_AMOUNT: u64 = 1_000_000 * 10u64.pow(9); // 1M tokens per day
-pub const TRANSACTION_COOLDOWN: i64 = 3600; // 1 hour between large transactions
-pub const LARGE_TRANSACTION_THRESHOLD: u64 = 100_000 * 10u64.pow(9); // 100K tokens
-
-#[program]
-pub mod treasury {
-    use super::*;
-
-    // Events
-    #[event]
-    pub struct TreasuryInitialized {
-        pub authority: Pubkey,
-        pub required_signers: u8,
-        pub timestamp: i64,
-    }
-
-    #[event]
-    pub struct TransactionProposed {
-        pub id: u64,
-        pub amount: u64,
-        pub destination: Pubkey,
-        pub proposer: Pubkey,
-        pub timestamp: i64,
-    }
-
-    #[event]
-    pub struct TransactionApproved {
-        pub id: u64,
-        pub approver: Pubkey,
-        pub timestamp: i64,
-    }
-
-    #[event]
-    pub struct TransactionExecuted {
-        pub id: u64,
-        pub amount: u64,
-        pub destination: Pubkey,
-        pub executor: Pubkey,
-        pub timestamp: i64,
-    }
-
-    #[event]
-    pub struct EmergencyAction {
-        pub action_type: EmergencyActionType,
-        pub initiator: Pubkey,
-        pub timestamp: i64,
-    }
-
-    pub fn initialize(ctx: Context<Initialize>, required_signers: u8) -> Result<()> {
-        require!(
-            required_signers > 0 && required_signers <= 5,
-            TreasuryError::InvalidSignerCount
-        );
-
-        let treasury = &mut ctx.accounts.treasury;
-        treasury.signers = vec![ctx.accounts.authority.key()];
-        treasury.required_signers = required_signers;
-        treasury.pending_transactions = Vec::new();
-        treasury.transaction_count = 0;
-        treasury.paused = false;
-        treasury.emergency_admin = ctx.accounts.authority.key();
-        treasury.daily_transaction_total = 0;
-        treasury.last_transaction_time = 0;
-        treasury.last_daily_reset = Clock::get()?.unix_timestamp;
-
-        emit!(TreasuryInitialized {
-            authority: ctx.accounts.authority.key(),
-            required_signers,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-
-        Ok(())
-    }
-
-    pub fn add_signer(ctx: Context<UpdateSigners>, new_signer: Pubkey) -> Result<()> {
-        let treasury = &mut ctx.accounts.treasury;
-        require!(!treasury.paused, TreasuryError::ContractPaused);
-        require!(
-            treasury.signers.len() < 5,
-            TreasuryError::MaxSignersReached
-        );
-        require!(
-            !treasury.signers.contains(&new_signer),
-            TreasuryError::SignerAlreadyExists
-        );
-
-        treasury.signers.push(new_signer);
-        Ok(())
-    }
-
-    pub fn propose_transaction(
-        ctx: Context<ProposeTransaction>,
-        amount: u64,
-        destination: Pubkey,
-        description: String,
-    ) -> Result<()> {
-        let treasury = &mut ctx.accounts.treasury;
-        require!(!treasury.paused, TreasuryError::ContractPaused);
-        require!(
-            treasury.signers.contains(&ctx.accounts.proposer.key()),
-            TreasuryError::UnauthorizedSigner
-        );
-
-        // Rate limiting checks
-        let current_time = Clock::get()?.unix_timestamp;
-        
-        // Reset daily total if 24 hours have passed
-        if current_time - treasury.last_daily_reset >= 86400 {
-            treasury.daily_transaction_total = 0;
-            treasury.last_daily_reset = current_time;
-        }
-
-        // Check daily limit
-        require!(
-            treasury.daily_transaction_total + amount <= MAX_DAILY_TRANSACTION_AMOUNT,
-            TreasuryError::DailyLimitExceeded
-        );
-
-        // Cooldown for large transactions
-        if amount >= LARGE_TRANSACTION_THRESHOLD {
-            require!(
-                current_time >= treasury.last_transaction_time + TRANSACTION_COOLDOWN,
-                TreasuryError::TransactionCooldownActive
-            );
-        }
-
-        let transaction = Transaction {
-            id: treasury.transaction_count,
-            amount,
-            destination,
-            description,
-            approvals: vec![ctx.accounts.proposer.key()],
-            executed: false,
-            created_at: current_time,
-        };
-
-        treasury.pending_transactions.push(transaction);
-        treasury.transaction_count += 1;
-
-        emit!(TransactionProposed {
-            id: treasury.transaction_count - 1,
-            amount,
-            destination,
-            proposer: ctx.accounts.proposer.key(),
-            timestamp: current_time,
-        });
-
-        Ok(())
-    }
-
-    pub fn approve_transaction(ctx: Context<ApproveTransaction>, transaction_id: u64) -> Result<()> {
-        let treasury = &mut ctx.accounts.treasury;
-        require!(!treasury.paused, TreasuryError::ContractPaused);
-
-        let transaction = treasury.pending_transactions
-            .iter_mut()
-            .find(|t| t.id == transaction_id)
-            .ok_or(TreasuryError::TransactionNotFound)?;
-
-        require!(
-            !transaction.executed,
-            TreasuryError::AlreadyExecuted
-        );
-        require!(
-            treasury.signers.contains(&ctx.accounts.signer.key()),
-            TreasuryError::UnauthorizedSigner
-        );
-        require!(
-            !transaction.approvals.contains(&ctx.accounts.signer.key()),
-            TreasuryError::AlreadyApproved
-        );
-
-        transaction.approvals.push(ctx.accounts.signer.key());
-
-        emit!(TransactionApproved {
-            id: transaction_id,
-            approver: ctx.accounts.signer.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-
-        Ok(())
-    }
-
-    pub fn execute_transaction(ctx: Context<ExecuteTransaction>, transaction_id: u64) -> Result<()> {
-        let treasury = &mut ctx.accounts.treasury;
-        require!(!treasury.paused, TreasuryError::ContractPaused);
-
-        let transaction = treasury.pending_transactions
-            .iter_mut()
-            .find(|t| t.id == transaction_id)
-            .ok_or(TreasuryError::TransactionNotFound)?;
-
-        require!(
-            !transaction.executed,
-            TreasuryError::AlreadyExecuted
-        );
-        require!(
-            transaction.approvals.len() >= treasury.required_signers as usize,
-            TreasuryError::InsufficientApprovals
-        );
-
-        let current_time = Clock::get()?.unix_timestamp;
-
-        // Update rate limiting state
-        if current_time - treasury.last_daily_reset >= 86400 {
-            treasury.daily_transaction_total = 0;
-            treasury.last_daily_reset = current_time;
-        }
-
-        treasury.daily_transaction_total += transaction.amount;
-        treasury.last_transaction_time = current_time;
-
-        // Transfer tokens
-        let transfer_instruction = Transfer {
-            from: ctx.accounts.treasury_vault.to_account_info(),
-            to: ctx.accounts.destination.to_account_info(),
-            authority: ctx.accounts.treasury.to_account_info(),
-        };
-
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                transfer_instruction,
-                &[&[&treasury.signers[0].to_bytes()[..], &[treasury.bump]]],
-            ),
-            transaction.amount,
-        )?;
-
-        transaction.executed = true;
-
-        emit!(TransactionExecuted {
-            id: transaction_id,
-            amount: transaction.amount,
-            destination: transaction.destination,
-            executor: ctx.accounts.treasury.key(),
-            timestamp: current_time,
-        });
-
-        Ok(())
-    }
-
-    pub fn pause(ctx: Context<EmergencyAction>) -> Result<()> {
-        let treasury = &mut ctx.accounts.treasury;
-        require!(
-            ctx.accounts.admin.key() == treasury.emergency_admin,
-            TreasuryError::Unauthorized
-        );
-        
-        treasury.paused = true;
-
-        emit!(EmergencyAction {
-            action_type: EmergencyActionType::Pause,
-            initiator: ctx.accounts.admin.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-
-        Ok(())
-    }
-
-    pub fn unpause(ctx: Context<EmergencyAction>) -> Result<()> {
-        let treasury = &mut ctx.accounts.treasury;
-        require!(
-            ctx.accounts.admin.key() == treasury.emergency_admin,
-            TreasuryError::Unauthorized
-        );
-        
-        treasury.paused = false;
-
-        emit!(EmergencyAction {
-            action_type: EmergencyActionType::Unpause,
-            initiator: ctx.accounts.admin.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-
-        Ok(())
-    }
-
-    pub fn update_emergency_admin(ctx: Context<EmergencyAction>, new_admin: Pubkey) -> Result<()> {
-        let treasury = &mut ctx.accounts.treasury;
-        require!(
-            ctx.accounts.admin.key() == treasury.emergency_admin,
-            TreasuryError::Unauthorized
-        );
-        
-        treasury.emergency_admin = new_admin;
-
-        emit!(EmergencyAction {
-            action_type: EmergencyActionType::UpdateAdmin,
-            initiator: ctx.accounts.admin.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-
-        Ok(())
-    }
-}
-
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(init, payer = authority, space = Treasury::LEN)]
-    pub treasury: Account<'info, Treasury>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-pub struct UpdateSigners<'info> {
-    #[account(mut)]
-    pub treasury: Account<'info, Treasury>,
-    pub authority: Signer<'info>,
-}
-
-#[derive(Accounts)]
-pub struct ProposeTransaction<'info> {
-    #[account(mut)]
-    pub treasury: Account<'info, Treasury>,
-    pub proposer: Signer<'info>,
-}
-
-#[derive(Accounts)]
-pub struct ApproveTransaction<'info> {
-    #[account(mut)]
-    pub treasury: Account<'info, Treasury>,
-    pub signer: Signer<'info>,
-}
-
-#[derive(Accounts)]
-pub struct ExecuteTransaction<'info> {
-    #[account(mut)]
-    pub treasury: Account<'info, Treasury>,
-    #[account(mut)]
-    pub treasury_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub destination: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-}
-
-#[derive(Accounts)]
-pub struct EmergencyAction<'info> {
-    #[account(mut)]
-    pub treasury: Account<'info, Treasury>,
-    pub admin: Signer<'info>,
-}
-
-#[account]
-pub struct Treasury {
-    pub signers: Vec<Pubkey>,
-    pub required_signers: u8,
-    pub pending_transactions: Vec<Transaction>,
-    pub transaction_count: u64,
-    pub bump: u8,
-    pub paused: bool,
-    pub emergency_admin: Pubkey,
-    pub daily_transaction_total: u64,
-    pub last_transaction_time: i64,
-    pub last_daily_reset: i64,
-}
-
-impl Treasury {
-    pub const LEN: usize = 8 + // discriminator
-        4 + (32 * 5) + // signers (max 5)
-        1 + // required_signers
-        4 + (Transaction::LEN * 100) + // pending_transactions (max 100)
-        8 + // transaction_count
-        1 + // bump
-        1 + // paused
-        32 + // emergency_admin
-        8 + // daily_transaction_total
-        8 + // last_transaction_time
-        8; // last_daily_reset
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct Transaction {
-    pub id: u64,
-    pub amount: u64,
-    pub destination: Pubkey,
-    pub description: String,
-    pub approvals: Vec<Pubkey>,
-    pub executed: bool,
-    pub created_at: i64,
-}
-
-impl Transaction {
-    pub const LEN: usize = 8 + // id
-        8 + // amount
-        32 + // destination
-        4 + 128 + // description
-        4 + (32 * 5) + // approvals (max 5)
-        1 + // executed
-        8; // created_at
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize)]
-pub enum EmergencyActionType {
-    Pause,
-    Unpause,
-    UpdateAdmin,
-}
-
-#[error_code]
-pub enum TreasuryError {
-    #[msg("Invalid number of required signers")]
-    InvalidSignerCount,
-    #[msg("Maximum number of signers reached")]
-    MaxSignersReached,
-    #[msg("Signer already exists")]
-    SignerAlreadyExists,
-    #[msg("Unauthorized signer")]
-    UnauthorizedSigner,
-    #[msg("Transaction not found")]
-    TransactionNotFound,
-    #[msg("Transaction already executed")]
-    AlreadyExecuted,
-    #[msg("Already approved this transaction")]
-    AlreadyApproved,
-    #[msg("Insufficient approvals to execute")]
-    InsufficientApprovals,
-    #[msg("Contract is paused")]
-    ContractPaused,
-    #[msg("Daily transaction limit exceeded")]
-    DailyLimitExceeded,
-    #[msg("Transaction cooldown period active")]
-    TransactionCooldownActive,
-    #[msg("Unauthorized")]
-    Unauthorized,
-}
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("TreasHZQVHDR2qXkP3FvPQ4FvXJKo6E9gA");
+
+// Rate limits live in TreasuryConfig and per-asset MintLimits, tuned by governance
+pub const DAILY_LIMIT_WINDOW: i64 = 86400;
+
+// Signer set limits
+pub const MAX_SIGNERS: usize = 5;
+
+// Transaction limits
+pub const MAX_TRANSACTION_INSTRUCTION_BYTES: usize = 1024;
+
+// PDA seeds
+pub const TRANSACTION_SEED: &[u8] = b"transaction";
+pub const TREASURY_AUTHORITY_SEED: &[u8] = b"treasury_authority";
+pub const INSTRUCTION_AUTHORITY_SEED: &[u8] = b"instruction_authority";
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const MINT_LIMITS_SEED: &[u8] = b"mint_limits";
+
+#[program]
+pub mod treasury {
+    use super::*;
+
+    // Events
+    #[event]
+    pub struct TreasuryInitialized {
+        pub authority: Pubkey,
+        pub required_signers: u8,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct TransactionProposed {
+        pub id: u64,
+        pub mint: Option<Pubkey>,
+        pub amount: u64,
+        pub destination: Pubkey,
+        pub proposer: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct SignerChangeProposed {
+        pub id: u64,
+        pub change: SignerChange,
+        pub proposer: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct SignersChanged {
+        pub id: u64,
+        pub change: SignerChange,
+        pub signers: Vec<Pubkey>,
+        pub required_signers: u8,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct VaultCreated {
+        pub mint: Pubkey,
+        pub vault: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct MintLimitsUpdated {
+        pub mint: Option<Pubkey>, // None for native SOL
+        pub old_limits: Option<AssetLimits>,
+        pub new_limits: AssetLimits,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct TreasuryBalance {
+        pub holder: Pubkey, // the treasury or instruction authority PDA
+        pub mint: Option<Pubkey>, // None for native SOL
+        pub balance: u64,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct TransactionApproved {
+        pub id: u64,
+        pub approver: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct TransactionExecuted {
+        pub id: u64,
+        pub mint: Option<Pubkey>,
+        pub amount: u64,
+        pub destination: Pubkey,
+        pub executor: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct TreasuryConfigUpdated {
+        pub old_config: TreasuryConfig,
+        pub new_config: TreasuryConfig,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct InstructionsExecuted {
+        pub id: u64,
+        pub instruction_count: u8,
+        pub executor: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct TransactionCancelled {
+        pub id: u64,
+        pub cancelled_by: Vec<Pubkey>,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct CancellationVoted {
+        pub id: u64,
+        pub signer: Pubkey,
+        pub votes: u8,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct ApprovalRevoked {
+        pub id: u64,
+        pub signer: Pubkey,
+        pub timestamp: i64,
+    }
+
+    #[event]
+    pub struct EmergencyAction {
+        pub action_type: EmergencyActionType,
+        pub initiator: Pubkey,
+        pub timestamp: i64,
+    }
+
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        signers: Vec<Pubkey>,
+        required_signers: u8,
+        governance_authority: Pubkey,
+        config: TreasuryConfig,
+    ) -> Result<()> {
+        require!(
+            !signers.is_empty() && signers.len() <= MAX_SIGNERS,
+            TreasuryError::InvalidSignerCount
+        );
+        require!(
+            signers.iter().enumerate().all(|(i, s)| !signers[..i].contains(s)),
+            TreasuryError::SignerAlreadyExists
+        );
+        require!(
+            required_signers > 0 && required_signers as usize <= signers.len(),
+            TreasuryError::InvalidSignerCount
+        );
+        require!(config.is_valid(), TreasuryError::InvalidConfig);
+
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.signers = signers;
+        treasury.required_signers = required_signers;
+        treasury.transaction_count = 0;
+        treasury.paused = false;
+        treasury.emergency_admin = ctx.accounts.authority.key();
+        treasury.last_large_transaction_time = 0;
+        treasury.governance_authority = governance_authority;
+        treasury.config = config;
+        treasury.authority_bump = ctx.bumps.treasury_authority;
+        treasury.instruction_authority_bump = ctx.bumps.instruction_authority;
+
+        emit!(TreasuryInitialized {
+            authority: ctx.accounts.authority.key(),
+            required_signers,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Only callable by the governance PDA, i.e. through an executed proposal
+    pub fn update_config(ctx: Context<UpdateConfig>, config: TreasuryConfig) -> Result<()> {
+        require!(config.is_valid(), TreasuryError::InvalidConfig);
+
+        let treasury = &mut ctx.accounts.treasury;
+        let old_config = std::mem::replace(&mut treasury.config, config.clone());
+
+        emit!(TreasuryConfigUpdated {
+            old_config,
+            new_config: config,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless, creates the treasury-authority-owned vault for a mint
+    pub fn create_vault(ctx: Context<CreateVault>) -> Result<()> {
+        emit!(VaultCreated {
+            mint: ctx.accounts.mint.key(),
+            vault: ctx.accounts.vault.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Only callable by the governance PDA, transfers of an asset need its limits
+    pub fn create_mint_limits(
+        ctx: Context<CreateMintLimits>,
+        mint: Option<Pubkey>,
+        limits: AssetLimits,
+    ) -> Result<()> {
+        require!(limits.is_valid(), TreasuryError::InvalidConfig);
+
+        let mint_limits = &mut ctx.accounts.mint_limits;
+        mint_limits.treasury = ctx.accounts.treasury.key();
+        mint_limits.mint = mint;
+        mint_limits.limits = limits.clone();
+        mint_limits.daily_total = 0;
+        mint_limits.last_daily_reset = Clock::get()?.unix_timestamp;
+        mint_limits.bump = ctx.bumps.mint_limits;
+
+        emit!(MintLimitsUpdated {
+            mint,
+            old_limits: None,
+            new_limits: limits,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Only callable by the governance PDA
+    pub fn update_mint_limits(ctx: Context<UpdateMintLimits>, limits: AssetLimits) -> Result<()> {
+        require!(limits.is_valid(), TreasuryError::InvalidConfig);
+
+        let mint_limits = &mut ctx.accounts.mint_limits;
+        let old_limits = std::mem::replace(&mut mint_limits.limits, limits.clone());
+
+        emit!(MintLimitsUpdated {
+            mint: mint_limits.mint,
+            old_limits: Some(old_limits),
+            new_limits: limits,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless, emits the balance of a vault, or of native SOL without one
+    pub fn report_balance(ctx: Context<ReportBalance>) -> Result<()> {
+        let (mint, balance) = match ctx.accounts.vault.as_ref() {
+            Some(vault) => (Some(vault.mint), vault.amount),
+            None => (None, ctx.accounts.treasury_authority.lamports()),
+        };
+
+        emit!(TreasuryBalance {
+            holder: ctx.accounts.treasury_authority.key(),
+            mint,
+            balance,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless, emits what the instruction authority holds in a token
+    // account, or in native SOL without one, so funds moved out for
+    // Instructions transactions stay visible
+    pub fn report_instruction_balance(ctx: Context<ReportInstructionBalance>) -> Result<()> {
+        let (mint, balance) = match ctx.accounts.token_account.as_ref() {
+            Some(account) => (Some(account.mint), account.amount),
+            None => (None, ctx.accounts.instruction_authority.lamports()),
+        };
+
+        emit!(TreasuryBalance {
+            holder: ctx.accounts.instruction_authority.key(),
+            mint,
+            balance,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Signer set changes are themselves transactions needing the current threshold
+    pub fn propose_signer_change(
+        ctx: Context<ProposeTransaction>,
+        change: SignerChange,
+        description: String,
+    ) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        require!(!treasury.paused, TreasuryError::ContractPaused);
+        require!(
+            treasury.signers.contains(&ctx.accounts.proposer.key()),
+            TreasuryError::UnauthorizedSigner
+        );
+
+        // Reject changes that could never be applied to the current set
+        let mut signers = treasury.signers.clone();
+        let mut required_signers = treasury.required_signers;
+        change.apply(&mut signers, &mut required_signers)?;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let transaction = &mut ctx.accounts.transaction;
+        transaction.treasury = treasury.key();
+        transaction.id = treasury.transaction_count;
+        transaction.proposer = ctx.accounts.proposer.key();
+        transaction.kind = TransactionKind::SignerChange(change.clone());
+        transaction.mint = None;
+        transaction.amount = 0;
+        transaction.destination = Pubkey::default();
+        transaction.instructions = Vec::new();
+        transaction.description = description;
+        transaction.approvals = vec![ctx.accounts.proposer.key()];
+        transaction.status = TransactionStatus::Pending;
+        transaction.cancellations = Vec::new();
+        transaction.created_at = current_time;
+        transaction.expires_at = current_time + treasury.config.transaction_lifetime;
+        transaction.bump = ctx.bumps.transaction;
+
+        treasury.transaction_count += 1;
+
+        emit!(SignerChangeProposed {
+            id: transaction.id,
+            change,
+            proposer: ctx.accounts.proposer.key(),
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn execute_signer_change(ctx: Context<UpdateSigners>) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        require!(!treasury.paused, TreasuryError::ContractPaused);
+        require!(
+            treasury.signers.contains(&ctx.accounts.authority.key()),
+            TreasuryError::UnauthorizedSigner
+        );
+
+        let transaction = &mut ctx.accounts.transaction;
+        transaction.require_pending(Clock::get()?.unix_timestamp)?;
+        require!(
+            treasury.current_approvals(transaction) >= treasury.required_signers as usize,
+            TreasuryError::InsufficientApprovals
+        );
+        let change = match &transaction.kind {
+            TransactionKind::SignerChange(change) => change.clone(),
+            _ => return err!(TreasuryError::InvalidTransactionKind),
+        };
+
+        let mut signers = treasury.signers.clone();
+        let mut required_signers = treasury.required_signers;
+        change.apply(&mut signers, &mut required_signers)?;
+
+        treasury.signers = signers;
+        treasury.required_signers = required_signers;
+        transaction.status = TransactionStatus::Executed;
+
+        emit!(SignersChanged {
+            id: transaction.id,
+            change,
+            signers: treasury.signers.clone(),
+            required_signers: treasury.required_signers,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // `mint: None` moves native SOL held by the treasury authority PDA
+    pub fn propose_transaction(
+        ctx: Context<ProposeTransfer>,
+        mint: Option<Pubkey>,
+        amount: u64,
+        destination: Pubkey,
+        description: String,
+    ) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        require!(!treasury.paused, TreasuryError::ContractPaused);
+        require!(
+            treasury.signers.contains(&ctx.accounts.proposer.key()),
+            TreasuryError::UnauthorizedSigner
+        );
+
+        // Rate limiting checks
+        let current_time = Clock::get()?.unix_timestamp;
+        let mint_limits = &mut ctx.accounts.mint_limits;
+        mint_limits.check(amount, current_time)?;
+
+        // Cooldown for large transactions
+        if amount >= mint_limits.limits.large_transaction_threshold {
+            require!(
+                current_time >= treasury.last_large_transaction_time + treasury.config.transaction_cooldown,
+                TreasuryError::TransactionCooldownActive
+            );
+        }
+
+        let transaction = &mut ctx.accounts.transaction;
+        transaction.treasury = treasury.key();
+        transaction.id = treasury.transaction_count;
+        transaction.proposer = ctx.accounts.proposer.key();
+        transaction.kind = TransactionKind::Transfer;
+        transaction.mint = mint;
+        transaction.amount = amount;
+        transaction.destination = destination;
+        transaction.instructions = Vec::new();
+        transaction.description = description;
+        transaction.approvals = vec![ctx.accounts.proposer.key()];
+        transaction.status = TransactionStatus::Pending;
+        transaction.cancellations = Vec::new();
+        transaction.created_at = current_time;
+        transaction.expires_at = current_time + treasury.config.transaction_lifetime;
+        transaction.bump = ctx.bumps.transaction;
+
+        treasury.transaction_count += 1;
+
+        emit!(TransactionProposed {
+            id: transaction.id,
+            mint,
+            amount,
+            destination,
+            proposer: ctx.accounts.proposer.key(),
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn approve_transaction(ctx: Context<ApproveTransaction>) -> Result<()> {
+        let treasury = &ctx.accounts.treasury;
+        require!(!treasury.paused, TreasuryError::ContractPaused);
+
+        require!(
+            treasury.signers.contains(&ctx.accounts.signer.key()),
+            TreasuryError::UnauthorizedSigner
+        );
+
+        let transaction = &mut ctx.accounts.transaction;
+
+        transaction.require_pending(Clock::get()?.unix_timestamp)?;
+        require!(
+            !transaction.approvals.contains(&ctx.accounts.signer.key()),
+            TreasuryError::AlreadyApproved
+        );
+
+        transaction.retain_current_signers(&treasury.signers);
+        transaction.approvals.push(ctx.accounts.signer.key());
+
+        emit!(TransactionApproved {
+            id: transaction.id,
+            approver: ctx.accounts.signer.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn revoke_approval(ctx: Context<ApproveTransaction>) -> Result<()> {
+        let treasury = &ctx.accounts.treasury;
+        require!(!treasury.paused, TreasuryError::ContractPaused);
+
+        let signer = ctx.accounts.signer.key();
+        require!(
+            treasury.signers.contains(&signer),
+            TreasuryError::UnauthorizedSigner
+        );
+
+        let transaction = &mut ctx.accounts.transaction;
+        transaction.require_pending(Clock::get()?.unix_timestamp)?;
+
+        let index = transaction.approvals
+            .iter()
+            .position(|a| *a == signer)
+            .ok_or(TreasuryError::NotApproved)?;
+        transaction.approvals.remove(index);
+
+        emit!(ApprovalRevoked {
+            id: transaction.id,
+            signer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn execute_transaction(ctx: Context<ExecuteTransaction>) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        require!(!treasury.paused, TreasuryError::ContractPaused);
+
+        let transaction = &mut ctx.accounts.transaction;
+
+        transaction.require_pending(Clock::get()?.unix_timestamp)?;
+        require!(
+            transaction.kind == TransactionKind::Transfer,
+            TreasuryError::InvalidTransactionKind
+        );
+        require!(
+            treasury.current_approvals(transaction) >= treasury.required_signers as usize,
+            TreasuryError::InsufficientApprovals
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Update rate limiting state, limits may have tightened since proposal
+        let mint_limits = &mut ctx.accounts.mint_limits;
+        mint_limits.check(transaction.amount, current_time)?;
+        mint_limits.daily_total += transaction.amount;
+        if transaction.amount >= mint_limits.limits.large_transaction_threshold {
+            require!(
+                current_time >= treasury.last_large_transaction_time + treasury.config.transaction_cooldown,
+                TreasuryError::TransactionCooldownActive
+            );
+            treasury.last_large_transaction_time = current_time;
+        }
+
+        transaction.status = TransactionStatus::Executed;
+        let transaction_id = transaction.id;
+        let mint = transaction.mint;
+        let amount = transaction.amount;
+        let destination = transaction.destination;
+        let treasury_key = treasury.key();
+        let authority_bump = treasury.authority_bump;
+        let signer_seeds: &[&[u8]] = &[
+            TREASURY_AUTHORITY_SEED,
+            treasury_key.as_ref(),
+            &[authority_bump],
+        ];
+
+        let balance = match mint {
+            Some(mint) => {
+                let vault = ctx.accounts.vault
+                    .as_mut()
+                    .filter(|v| v.mint == mint)
+                    .ok_or(TreasuryError::InvalidVault)?;
+
+                // Transfer tokens
+                let transfer_instruction = Transfer {
+                    from: vault.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.treasury_authority.to_account_info(),
+                };
+
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        transfer_instruction,
+                        &[signer_seeds],
+                    ),
+                    amount,
+                )?;
+
+                vault.reload()?;
+                vault.amount
+            }
+            None => {
+                // The authority PDA must stay rent-exempt to keep holding SOL
+                require!(
+                    ctx.accounts.treasury_authority.lamports().saturating_sub(amount)
+                        >= Rent::get()?.minimum_balance(0),
+                    TreasuryError::BelowRentExemption
+                );
+
+                system_program::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.treasury_authority.to_account_info(),
+                            to: ctx.accounts.destination.to_account_info(),
+                        },
+                        &[signer_seeds],
+                    ),
+                    amount,
+                )?;
+
+                ctx.accounts.treasury_authority.lamports()
+            }
+        };
+
+        emit!(TransactionExecuted {
+            id: transaction_id,
+            mint,
+            amount,
+            destination,
+            executor: ctx.accounts.treasury.key(),
+            timestamp: current_time,
+        });
+
+        emit!(TreasuryBalance {
+            holder: ctx.accounts.treasury_authority.key(),
+            mint,
+            balance,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    // Arbitrary instructions are signed by the instruction authority PDA, which
+    // owns no vaults, so funds only leave the vaults through rate-limited
+    // transfers. To stake, swap or otherwise act on treasury assets, first
+    // execute a Transfer to the instruction authority (or a token account it
+    // owns), then an Instructions transaction acting on those holdings. Any
+    // account the authority owns, including its stake or LP positions, can
+    // have its authority set back to the treasury authority PDA the same way.
+    pub fn propose_instructions(
+        ctx: Context<ProposeTransaction>,
+        instructions: Vec<TransactionInstruction>,
+        description: String,
+    ) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        require!(!treasury.paused, TreasuryError::ContractPaused);
+        require!(
+            treasury.signers.contains(&ctx.accounts.proposer.key()),
+            TreasuryError::UnauthorizedSigner
+        );
+        require!(
+            !instructions.is_empty() && instructions.len() <= u8::MAX as usize,
+            TreasuryError::InvalidInstructions
+        );
+        require!(
+            instructions.try_to_vec()?.len() <= 4 + MAX_TRANSACTION_INSTRUCTION_BYTES,
+            TreasuryError::InstructionsTooLarge
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let transaction = &mut ctx.accounts.transaction;
+        transaction.treasury = treasury.key();
+        transaction.id = treasury.transaction_count;
+        transaction.proposer = ctx.accounts.proposer.key();
+        transaction.kind = TransactionKind::Instructions;
+        transaction.mint = None;
+        transaction.amount = 0;
+        transaction.destination = Pubkey::default();
+        transaction.instructions = instructions;
+        transaction.description = description;
+        transaction.approvals = vec![ctx.accounts.proposer.key()];
+        transaction.status = TransactionStatus::Pending;
+        transaction.cancellations = Vec::new();
+        transaction.created_at = current_time;
+        transaction.expires_at = current_time + treasury.config.transaction_lifetime;
+        transaction.bump = ctx.bumps.transaction;
+
+        treasury.transaction_count += 1;
+
+        emit!(TransactionProposed {
+            id: transaction.id,
+            mint: None,
+            amount: 0,
+            destination: Pubkey::default(),
+            proposer: ctx.accounts.proposer.key(),
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    pub fn execute_instructions(ctx: Context<ExecuteInstructions>) -> Result<()> {
+        let treasury = &ctx.accounts.treasury;
+        require!(!treasury.paused, TreasuryError::ContractPaused);
+
+        let transaction = &mut ctx.accounts.transaction;
+        transaction.require_pending(Clock::get()?.unix_timestamp)?;
+        require!(
+            transaction.kind == TransactionKind::Instructions,
+            TreasuryError::InvalidTransactionKind
+        );
+        require!(
+            treasury.current_approvals(transaction) >= treasury.required_signers as usize,
+            TreasuryError::InsufficientApprovals
+        );
+
+        transaction.status = TransactionStatus::Executed;
+        let transaction_id = transaction.id;
+        let instructions = transaction.instructions.clone();
+        let treasury_key = treasury.key();
+        let authority_bump = treasury.instruction_authority_bump;
+
+        // Persist state before the CPIs so an instruction that re-enters the
+        // treasury sees the transaction as executed
+        ctx.accounts.transaction.exit(&crate::ID)?;
+
+        let signer_seeds: &[&[u8]] = &[
+            INSTRUCTION_AUTHORITY_SEED,
+            treasury_key.as_ref(),
+            &[authority_bump],
+        ];
+        for instruction in instructions.iter() {
+            invoke_signed(
+                &instruction.to_instruction(),
+                ctx.remaining_accounts,
+                &[signer_seeds],
+            )?;
+        }
+
+        ctx.accounts.transaction.reload()?;
+
+        emit!(InstructionsExecuted {
+            id: transaction_id,
+            instruction_count: instructions.len() as u8,
+            executor: ctx.accounts.executor.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // A proposer who is still a signer cancels outright, other signers vote until the threshold
+    pub fn cancel_transaction(ctx: Context<CancelTransaction>) -> Result<()> {
+        let treasury = &ctx.accounts.treasury;
+        let signer = ctx.accounts.signer.key();
+        require!(
+            treasury.signers.contains(&signer),
+            TreasuryError::UnauthorizedSigner
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let transaction = &mut ctx.accounts.transaction;
+        transaction.require_pending(current_time)?;
+        require!(
+            !transaction.cancellations.contains(&signer),
+            TreasuryError::AlreadyVotedToCancel
+        );
+        transaction.retain_current_signers(&treasury.signers);
+        transaction.cancellations.push(signer);
+
+        let votes = transaction.cancellations
+            .iter()
+            .filter(|c| treasury.signers.contains(c))
+            .count();
+        if signer != transaction.proposer && votes < treasury.required_signers as usize {
+            emit!(CancellationVoted {
+                id: transaction.id,
+                signer,
+                votes: votes as u8,
+                timestamp: current_time,
+            });
+            return Ok(());
+        }
+
+        transaction.status = TransactionStatus::Cancelled;
+
+        emit!(TransactionCancelled {
+            id: transaction.id,
+            cancelled_by: transaction.cancellations.clone(),
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    // Permissionless, returns the rent of a finished or expired transaction to its proposer
+    pub fn close_transaction(ctx: Context<CloseTransaction>) -> Result<()> {
+        let transaction = &ctx.accounts.transaction;
+        require!(
+            transaction.status != TransactionStatus::Pending
+                || Clock::get()?.unix_timestamp > transaction.expires_at,
+            TreasuryError::TransactionPending
+        );
+
+        Ok(())
+    }
+
+    pub fn pause(ctx: Context<EmergencyAction>) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        require!(
+            ctx.accounts.admin.key() == treasury.emergency_admin,
+            TreasuryError::Unauthorized
+        );
+        
+        treasury.paused = true;
+
+        emit!(EmergencyAction {
+            action_type: EmergencyActionType::Pause,
+            initiator: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn unpause(ctx: Context<EmergencyAction>) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        require!(
+            ctx.accounts.admin.key() == treasury.emergency_admin,
+            TreasuryError::Unauthorized
+        );
+        
+        treasury.paused = false;
+
+        emit!(EmergencyAction {
+            action_type: EmergencyActionType::Unpause,
+            initiator: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_emergency_admin(ctx: Context<EmergencyAction>, new_admin: Pubkey) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        require!(
+            ctx.accounts.admin.key() == treasury.emergency_admin,
+            TreasuryError::Unauthorized
+        );
+        
+        treasury.emergency_admin = new_admin;
+
+        emit!(EmergencyAction {
+            action_type: EmergencyActionType::UpdateAdmin,
+            initiator: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = authority, space = Treasury::LEN)]
+    pub treasury: Account<'info, Treasury>,
+    /// CHECK: PDA that owns treasury funds and signs executed transfers, holds no data
+    #[account(seeds = [TREASURY_AUTHORITY_SEED, treasury.key().as_ref()], bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    /// CHECK: PDA that signs Instructions transactions, owns no vaults and holds no data
+    #[account(seeds = [INSTRUCTION_AUTHORITY_SEED, treasury.key().as_ref()], bump)]
+    pub instruction_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(mut, has_one = governance_authority)]
+    pub treasury: Account<'info, Treasury>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVault<'info> {
+    pub treasury: Account<'info, Treasury>,
+    /// CHECK: PDA that owns the vault, holds no data
+    #[account(
+        seeds = [TREASURY_AUTHORITY_SEED, treasury.key().as_ref()],
+        bump = treasury.authority_bump
+    )]
+    pub treasury_authority: UncheckedAccount<'info>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [VAULT_SEED, treasury.key().as_ref(), mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = treasury_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Option<Pubkey>)]
+pub struct CreateMintLimits<'info> {
+    #[account(has_one = governance_authority)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        init,
+        payer = payer,
+        space = MintLimits::LEN,
+        seeds = [MINT_LIMITS_SEED, treasury.key().as_ref(), asset_key(&mint).as_ref()],
+        bump
+    )]
+    pub mint_limits: Account<'info, MintLimits>,
+    pub governance_authority: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMintLimits<'info> {
+    #[account(has_one = governance_authority)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        has_one = treasury,
+        seeds = [MINT_LIMITS_SEED, treasury.key().as_ref(), asset_key(&mint_limits.mint).as_ref()],
+        bump = mint_limits.bump
+    )]
+    pub mint_limits: Account<'info, MintLimits>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReportBalance<'info> {
+    pub treasury: Account<'info, Treasury>,
+    /// CHECK: PDA holding the treasury's native SOL
+    #[account(
+        seeds = [TREASURY_AUTHORITY_SEED, treasury.key().as_ref()],
+        bump = treasury.authority_bump
+    )]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(token::authority = treasury_authority)]
+    pub vault: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct ReportInstructionBalance<'info> {
+    pub treasury: Account<'info, Treasury>,
+    /// CHECK: PDA holding funds moved out for Instructions transactions
+    #[account(
+        seeds = [INSTRUCTION_AUTHORITY_SEED, treasury.key().as_ref()],
+        bump = treasury.instruction_authority_bump
+    )]
+    pub instruction_authority: UncheckedAccount<'info>,
+    #[account(token::authority = instruction_authority)]
+    pub token_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSigners<'info> {
+    #[account(mut)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        has_one = treasury,
+        seeds = [TRANSACTION_SEED, treasury.key().as_ref(), &transaction.id.to_le_bytes()],
+        bump = transaction.bump
+    )]
+    pub transaction: Account<'info, Transaction>,
+    pub authority: Signer<'info>, // any current signer may execute an approved change
+}
+
+#[derive(Accounts)]
+pub struct ProposeTransaction<'info> {
+    #[account(mut)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        init,
+        payer = proposer,
+        space = Transaction::LEN,
+        seeds = [TRANSACTION_SEED, treasury.key().as_ref(), &treasury.transaction_count.to_le_bytes()],
+        bump
+    )]
+    pub transaction: Account<'info, Transaction>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Option<Pubkey>)]
+pub struct ProposeTransfer<'info> {
+    #[account(mut)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        init,
+        payer = proposer,
+        space = Transaction::LEN,
+        seeds = [TRANSACTION_SEED, treasury.key().as_ref(), &treasury.transaction_count.to_le_bytes()],
+        bump
+    )]
+    pub transaction: Account<'info, Transaction>,
+    #[account(
+        mut,
+        has_one = treasury,
+        seeds = [MINT_LIMITS_SEED, treasury.key().as_ref(), asset_key(&mint).as_ref()],
+        bump = mint_limits.bump
+    )]
+    pub mint_limits: Account<'info, MintLimits>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveTransaction<'info> {
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        has_one = treasury,
+        seeds = [TRANSACTION_SEED, treasury.key().as_ref(), &transaction.id.to_le_bytes()],
+        bump = transaction.bump
+    )]
+    pub transaction: Account<'info, Transaction>,
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteTransaction<'info> {
+    #[account(mut)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        has_one = treasury,
+        seeds = [TRANSACTION_SEED, treasury.key().as_ref(), &transaction.id.to_le_bytes()],
+        bump = transaction.bump
+    )]
+    pub transaction: Account<'info, Transaction>,
+    #[account(
+        mut,
+        has_one = treasury,
+        seeds = [MINT_LIMITS_SEED, treasury.key().as_ref(), asset_key(&transaction.mint).as_ref()],
+        bump = mint_limits.bump
+    )]
+    pub mint_limits: Account<'info, MintLimits>,
+    /// CHECK: PDA that owns the vaults and holds native SOL, holds no data
+    #[account(
+        mut,
+        seeds = [TREASURY_AUTHORITY_SEED, treasury.key().as_ref()],
+        bump = treasury.authority_bump
+    )]
+    pub treasury_authority: UncheckedAccount<'info>,
+    // Required for SPL transfers, omitted for native SOL
+    #[account(mut, token::authority = treasury_authority)]
+    pub vault: Option<Account<'info, TokenAccount>>,
+    /// CHECK: a token account for SPL transfers or any account for SOL, checked against the transaction
+    #[account(
+        mut,
+        constraint = destination.key() == transaction.destination @ TreasuryError::InvalidDestination
+    )]
+    pub destination: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteInstructions<'info> {
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        has_one = treasury,
+        seeds = [TRANSACTION_SEED, treasury.key().as_ref(), &transaction.id.to_le_bytes()],
+        bump = transaction.bump
+    )]
+    pub transaction: Account<'info, Transaction>,
+    pub executor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTransaction<'info> {
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        has_one = treasury,
+        seeds = [TRANSACTION_SEED, treasury.key().as_ref(), &transaction.id.to_le_bytes()],
+        bump = transaction.bump
+    )]
+    pub transaction: Account<'info, Transaction>,
+    pub signer: Signer<'info>, // must be a current signer
+}
+
+#[derive(Accounts)]
+pub struct CloseTransaction<'info> {
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        close = proposer,
+        has_one = treasury,
+        has_one = proposer,
+        seeds = [TRANSACTION_SEED, treasury.key().as_ref(), &transaction.id.to_le_bytes()],
+        bump = transaction.bump
+    )]
+    pub transaction: Account<'info, Transaction>,
+    /// CHECK: receives the rent, checked against transaction.proposer
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyAction<'info> {
+    #[account(mut)]
+    pub treasury: Account<'info, Treasury>,
+    pub admin: Signer<'info>,
+}
+
+#[account]
+pub struct Treasury {
+    pub signers: Vec<Pubkey>,
+    pub required_signers: u8,
+    pub transaction_count: u64, // seeds the next transaction PDA
+    pub authority_bump: u8,
+    pub instruction_authority_bump: u8,
+    pub paused: bool,
+    pub emergency_admin: Pubkey,
+    pub last_large_transaction_time: i64,
+    pub governance_authority: Pubkey,
+    pub config: TreasuryConfig,
+}
+
+impl Treasury {
+    pub const LEN: usize = 8 + // discriminator
+        4 + (32 * MAX_SIGNERS) + // signers
+        1 + // required_signers
+        8 + // transaction_count
+        1 + // authority_bump
+        1 + // instruction_authority_bump
+        1 + // paused
+        32 + // emergency_admin
+        8 + // last_large_transaction_time
+        32 + // governance_authority
+        TreasuryConfig::LEN; // config
+
+    // Approvals from keys that have since been removed no longer count
+    pub fn current_approvals(&self, transaction: &Transaction) -> usize {
+        transaction.approvals
+            .iter()
+            .filter(|a| self.signers.contains(a))
+            .count()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TreasuryConfig {
+    pub transaction_cooldown: i64, // between large transactions of any asset
+    pub transaction_lifetime: i64, // after which a pending transaction can no longer execute
+}
+
+impl TreasuryConfig {
+    pub const LEN: usize = 8 + 8;
+
+    pub fn is_valid(&self) -> bool {
+        self.transaction_cooldown >= 0 && self.transaction_lifetime > 0
+    }
+}
+
+// Native SOL limits live under the default pubkey
+pub fn asset_key(mint: &Option<Pubkey>) -> Pubkey {
+    mint.unwrap_or_default()
+}
+
+// Per-asset transfer limits and daily usage
+#[account]
+pub struct MintLimits {
+    pub treasury: Pubkey,
+    pub mint: Option<Pubkey>, // None for native SOL
+    pub limits: AssetLimits,
+    pub daily_total: u64,
+    pub last_daily_reset: i64,
+    pub bump: u8,
+}
+
+impl MintLimits {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // treasury
+        1 + 32 + // mint
+        AssetLimits::LEN + // limits
+        8 + // daily_total
+        8 + // last_daily_reset
+        1; // bump
+
+    // Resets the daily window if it has passed, then checks the daily limit
+    pub fn check(&mut self, amount: u64, current_time: i64) -> Result<()> {
+        if current_time - self.last_daily_reset >= DAILY_LIMIT_WINDOW {
+            self.daily_total = 0;
+            self.last_daily_reset = current_time;
+        }
+
+        require!(
+            self.daily_total + amount <= self.limits.max_daily_amount,
+            TreasuryError::DailyLimitExceeded
+        );
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AssetLimits {
+    pub max_daily_amount: u64, // in the asset's base units
+    pub large_transaction_threshold: u64,
+}
+
+impl AssetLimits {
+    pub const LEN: usize = 8 + 8;
+
+    pub fn is_valid(&self) -> bool {
+        self.large_transaction_threshold <= self.max_daily_amount
+    }
+}
+
+#[account]
+pub struct Transaction {
+    pub treasury: Pubkey,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub kind: TransactionKind,
+    pub mint: Option<Pubkey>, // None for native SOL
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub instructions: Vec<TransactionInstruction>, // Instructions kind only
+    pub description: String,
+    pub approvals: Vec<Pubkey>,
+    pub status: TransactionStatus,
+    pub cancellations: Vec<Pubkey>, // signers who voted to cancel
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl Transaction {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // treasury
+        8 + // id
+        32 + // proposer
+        TransactionKind::LEN + // kind
+        1 + 32 + // mint
+        8 + // amount
+        32 + // destination
+        4 + MAX_TRANSACTION_INSTRUCTION_BYTES + // instructions
+        4 + 128 + // description
+        4 + (32 * MAX_SIGNERS) + // approvals
+        1 + // status
+        4 + (32 * MAX_SIGNERS) + // cancellations
+        8 + // created_at
+        8 + // expires_at
+        1; // bump
+
+    // Drops approvals and cancellations from removed signers, so rotations
+    // cannot grow either list past its MAX_SIGNERS allocation
+    pub fn retain_current_signers(&mut self, signers: &[Pubkey]) {
+        self.approvals.retain(|a| signers.contains(a));
+        self.cancellations.retain(|c| signers.contains(c));
+    }
+
+    pub fn require_pending(&self, current_time: i64) -> Result<()> {
+        require!(
+            self.status != TransactionStatus::Executed,
+            TreasuryError::AlreadyExecuted
+        );
+        require!(
+            self.status != TransactionStatus::Cancelled,
+            TreasuryError::TransactionCancelled
+        );
+        require!(
+            current_time <= self.expires_at,
+            TreasuryError::TransactionExpired
+        );
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub enum TransactionStatus {
+    Pending, // expired once past expires_at
+    Executed,
+    Cancelled,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub enum TransactionKind {
+    Transfer, // moves `amount` to `destination`
+    SignerChange(SignerChange),
+    Instructions, // invokes `instructions`, signed by the instruction authority PDA
+}
+
+impl TransactionKind {
+    pub const LEN: usize = 1 + SignerChange::LEN;
+}
+
+// An instruction invoked by `execute_instructions`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TransactionInstruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<TransactionAccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl TransactionInstruction {
+    pub fn to_instruction(&self) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: self.accounts
+                .iter()
+                .map(|meta| AccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TransactionAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub enum SignerChange {
+    AddSigner(Pubkey),
+    RemoveSigner(Pubkey),
+    ReplaceSigner { old: Pubkey, new: Pubkey },
+    SetThreshold(u8),
+}
+
+impl SignerChange {
+    pub const LEN: usize = 1 + 32 + 32;
+
+    // Applies the change, keeping 1 <= required_signers <= signers.len()
+    pub fn apply(&self, signers: &mut Vec<Pubkey>, required_signers: &mut u8) -> Result<()> {
+        match self {
+            SignerChange::AddSigner(signer) => {
+                require!(signers.len() < MAX_SIGNERS, TreasuryError::MaxSignersReached);
+                require!(!signers.contains(signer), TreasuryError::SignerAlreadyExists);
+                signers.push(*signer);
+            }
+            SignerChange::RemoveSigner(signer) => {
+                let index = signers
+                    .iter()
+                    .position(|s| s == signer)
+                    .ok_or(TreasuryError::SignerNotFound)?;
+                signers.remove(index);
+            }
+            SignerChange::ReplaceSigner { old, new } => {
+                require!(!signers.contains(new), TreasuryError::SignerAlreadyExists);
+                let index = signers
+                    .iter()
+                    .position(|s| s == old)
+                    .ok_or(TreasuryError::SignerNotFound)?;
+                signers[index] = *new;
+            }
+            SignerChange::SetThreshold(threshold) => *required_signers = *threshold,
+        }
+
+        require!(
+            *required_signers > 0 && *required_signers as usize <= signers.len(),
+            TreasuryError::InvalidSignerCount
+        );
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub enum EmergencyActionType {
+    Pause,
+    Unpause,
+    UpdateAdmin,
+}
+
+#[error_code]
+pub enum TreasuryError {
+    #[msg("Invalid number of required signers")]
+    InvalidSignerCount,
+    #[msg("Maximum number of signers reached")]
+    MaxSignersReached,
+    #[msg("Signer already exists")]
+    SignerAlreadyExists,
+    #[msg("Unauthorized signer")]
+    UnauthorizedSigner,
+    #[msg("Transaction not found")]
+    TransactionNotFound,
+    #[msg("Transaction already executed")]
+    AlreadyExecuted,
+    #[msg("Already approved this transaction")]
+    AlreadyApproved,
+    #[msg("Insufficient approvals to execute")]
+    InsufficientApprovals,
+    #[msg("Contract is paused")]
+    ContractPaused,
+    #[msg("Daily transaction limit exceeded")]
+    DailyLimitExceeded,
+    #[msg("Transaction cooldown period active")]
+    TransactionCooldownActive,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Invalid treasury configuration")]
+    InvalidConfig,
+    #[msg("Signer not found")]
+    SignerNotFound,
+    #[msg("Instruction does not match the transaction kind")]
+    InvalidTransactionKind,
+    #[msg("Transaction has been cancelled")]
+    TransactionCancelled,
+    #[msg("Transaction is still pending")]
+    TransactionPending,
+    #[msg("Destination does not match the transaction")]
+    InvalidDestination,
+    #[msg("Transaction must carry between 1 and 255 instructions")]
+    InvalidInstructions,
+    #[msg("Instructions exceed maximum size")]
+    InstructionsTooLarge,
+    #[msg("Vault missing or for the wrong mint")]
+    InvalidVault,
+    #[msg("Transaction has expired")]
+    TransactionExpired,
+    #[msg("Signer has not approved this transaction")]
+    NotApproved,
+    #[msg("Already voted to cancel this transaction")]
+    AlreadyVotedToCancel,
+    #[msg("Transfer would leave the treasury authority below rent exemption")]
+    BelowRentExemption,
+}